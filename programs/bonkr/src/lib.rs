@@ -1,5 +1,16 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer, MintTo, Burn};
+use anchor_spl::token_interface::{
+    self, Mint, TokenInterface, TokenAccount, TransferChecked, MintTo, Burn, SetAuthority,
+    TokenMetadataInitialize, TokenMetadataUpdateField,
+};
+use anchor_spl::token_interface::spl_token_2022::{
+    self,
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions, metadata_pointer::MetadataPointer},
+    instruction::AuthorityType,
+};
+use anchor_spl::token_interface::spl_pod::optional_keys::OptionalNonZeroPubkey;
+use anchor_spl::token_interface::spl_token_metadata_interface::state::{Field, TokenMetadata};
+use anchor_spl::token_2022::Token2022;
 use anchor_spl::associated_token::AssociatedToken;
 
 declare_id!("14cdFgoduHhJQtheRPn3GF48YLR89jMcucdpkJKgsq4w");
@@ -13,6 +24,36 @@ pub const PLATFORM_FEE_BP: u64 = 100;
 pub const CREATOR_FEE_BP: u64 = 50;
 pub const TOTAL_FEE_BP: u64 = 200;
 pub const BP_DENOMINATOR: u64 = 10_000;
+pub const DEFAULT_GUARDIAN_PAUSE_DURATION: i64 = 24 * 60 * 60;
+pub const PRICE_PRECISION: u128 = 1_000_000_000_000_000_000;
+pub const MAX_ALLOWLIST_DURATION: i64 = 7 * 24 * 60 * 60;
+pub const MAX_ALLOWLIST_PROOF_LEN: usize = 32;
+pub const MAX_NAME_LEN: usize = 32;
+pub const MAX_SYMBOL_LEN: usize = 10;
+pub const MAX_URI_LEN: usize = 200;
+pub const MAX_LINK_LEN: usize = 100;
+pub const MAX_HANDLE_LEN: usize = 64;
+pub const MAX_DESCRIPTION_LEN: usize = 280;
+pub const MAX_RECENT_MINTS: usize = 10;
+pub const LAUNCH_DAY: i64 = 24 * 60 * 60;
+pub const MAX_POW_DIFFICULTY: u8 = 64;
+pub const MAX_POW_SLOT_AGE: u64 = 150;
+pub const LAUNCH_REVEAL_WINDOW_SLOTS: u64 = 150;
+pub const LAUNCH_EXCLUSIVITY_DURATION: i64 = 24 * 60 * 60;
+pub const MAX_SANDWICH_GUARD_SLOTS: u64 = 150;
+pub const MAX_GRADUATION_BOUNTY: u64 = 100_000_000;
+
+pub const CONFIG_VERSION: u8 = 1;
+pub const TOKEN_STATE_VERSION: u8 = 1;
+
+pub const PAUSE_BUY: u8 = 1 << 0;
+pub const PAUSE_SELL: u8 = 1 << 1;
+pub const PAUSE_CREATE: u8 = 1 << 2;
+pub const PAUSE_TRADING: u8 = PAUSE_BUY | PAUSE_SELL;
+pub const PAUSE_ALL: u8 = PAUSE_TRADING | PAUSE_CREATE;
+
+pub const PAUSE_REASON_LP_WITHDRAWN: u8 = 255;
+pub const PAUSE_REASON_EMERGENCY_WITHDRAW: u8 = 254;
 
 #[program]
 pub mod bonkr {
@@ -21,16 +62,94 @@ pub mod bonkr {
     pub fn initialize(
         ctx: Context<Initialize>,
         creation_fee: u64,
+        timelock_delay: i64,
     ) -> Result<()> {
+        require!(timelock_delay >= 0, BonkrError::InvalidAmount);
+
         let config = &mut ctx.accounts.config;
         config.authority = ctx.accounts.authority.key();
         config.platform_fee_recipient = ctx.accounts.platform_fee_recipient.key();
         config.oracle = ctx.accounts.authority.key();
+        config.guardian = ctx.accounts.authority.key();
         config.creation_fee = creation_fee;
         config.token_count = 0;
-        config.is_paused = false;
+        config.pause_flags = 0;
         config.sol_price_usd = 200 * 1_000_000;
+        config.timelock_delay = timelock_delay;
+        config.action_count = 0;
+        config.guardian_pause_duration = DEFAULT_GUARDIAN_PAUSE_DURATION;
+        config.guardian_pause_flags = 0;
+        config.guardian_paused_until = 0;
+        config.breaker_threshold_bp = 0;
+        config.breaker_window_slots = 0;
+        config.breaker_cooldown_slots = 0;
+        config.max_wallet_buy_bp = 0;
+        config.wallet_cap_duration = 0;
+        config.max_dev_buy_bp = 0;
+        config.symbol_uniqueness = false;
+        config.creator_launch_cooldown = 0;
+        config.max_launches_per_day = 0;
+        config.pow_difficulty = 0;
+        config.sandwich_guard_slots = 0;
+        config.sandwich_guard_duration = 0;
+        config.max_price_impact_bp = 0;
+        config.graduation_bounty = 0;
+        config.market_cap_basis = MarketCapBasis::FullyDiluted;
+        config.guardian_pause_cooldown_until = 0;
         config.bump = ctx.bumps.config;
+        config.version = CONFIG_VERSION;
+        Ok(())
+    }
+
+    // Upgrades a config written by the first deployment. Legacy `is_paused`
+    // paused everything, so it maps onto `PAUSE_ALL`.
+    pub fn migrate_config(ctx: Context<MigrateConfig>, timelock_delay: i64) -> Result<()> {
+        require!(timelock_delay >= 0, BonkrError::InvalidAmount);
+
+        let config_info = ctx.accounts.config.to_account_info();
+        let mut config: GlobalConfig = load_for_migration(
+            &config_info,
+            8 + GlobalConfig::INIT_SPACE,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+        )?;
+        require!(config.version < CONFIG_VERSION, BonkrError::AlreadyMigrated);
+        require!(config.authority == ctx.accounts.authority.key(), BonkrError::Unauthorized);
+
+        if config.pause_flags != 0 {
+            config.pause_flags = PAUSE_ALL;
+        }
+        config.guardian = config.authority;
+        config.timelock_delay = timelock_delay;
+        config.guardian_pause_duration = DEFAULT_GUARDIAN_PAUSE_DURATION;
+        config.market_cap_basis = MarketCapBasis::FullyDiluted;
+        config.version = CONFIG_VERSION;
+
+        let mut data = config_info.try_borrow_mut_data()?;
+        config.try_serialize(&mut &mut data[..])?;
+        Ok(())
+    }
+
+    // Upgrades a token written by the first deployment. Anyone may run it;
+    // every new field starts disabled and legacy `is_paused` maps onto
+    // `PAUSE_TRADING`.
+    pub fn migrate_token_state(ctx: Context<MigrateTokenState>) -> Result<()> {
+        let token_state_info = ctx.accounts.token_state.to_account_info();
+        let mut token_state: TokenState = load_for_migration(
+            &token_state_info,
+            8 + TokenState::INIT_SPACE,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+        )?;
+        require!(token_state.version < TOKEN_STATE_VERSION, BonkrError::AlreadyMigrated);
+
+        if token_state.pause_flags != 0 {
+            token_state.pause_flags = PAUSE_TRADING;
+        }
+        token_state.version = TOKEN_STATE_VERSION;
+
+        let mut data = token_state_info.try_borrow_mut_data()?;
+        token_state.try_serialize(&mut &mut data[..])?;
         Ok(())
     }
 
@@ -45,92 +164,55 @@ pub mod bonkr {
         Ok(())
     }
 
-    pub fn create_token(
-        ctx: Context<CreateToken>,
-        name: String,
-        symbol: String,
-        uri: String,
-        initial_buy_sol: u64,
-    ) -> Result<()> {
-        require!(!ctx.accounts.config.is_paused, BonkrError::FactoryPaused);
-        require!(name.len() <= 32, BonkrError::NameTooLong);
-        require!(symbol.len() <= 10, BonkrError::SymbolTooLong);
+    pub fn create_token(ctx: Context<CreateToken>, params: LaunchParams) -> Result<()> {
+        check_deadline(params.deadline_unix_ts, params.max_slot)?;
+        require!(ctx.accounts.launch_commitment.is_none(), BonkrError::InvalidLaunchCommitment);
+        create_token_internal(ctx, params, None)
+    }
 
-        let creation_fee = ctx.accounts.config.creation_fee;
-        if creation_fee > 0 {
-            let cpi_context = CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.creator.to_account_info(),
-                    to: ctx.accounts.platform_fee_recipient.to_account_info(),
-                },
-            );
-            anchor_lang::system_program::transfer(cpi_context, creation_fee)?;
-        }
+    pub fn commit_launch(ctx: Context<CommitLaunch>, commitment: [u8; 32]) -> Result<()> {
+        let launch_commitment = &mut ctx.accounts.launch_commitment;
+        launch_commitment.creator = ctx.accounts.creator.key();
+        launch_commitment.commitment = commitment;
+        launch_commitment.committed_slot = Clock::get()?.slot;
+        launch_commitment.bump = ctx.bumps.launch_commitment;
 
-        let token_state = &mut ctx.accounts.token_state;
-        token_state.mint = ctx.accounts.mint.key();
-        token_state.creator = ctx.accounts.creator.key();
-        token_state.name = name;
-        token_state.symbol = symbol;
-        token_state.uri = uri;
-        token_state.virtual_sol_reserve = INITIAL_VIRTUAL_SOL;
-        token_state.virtual_token_reserve = INITIAL_VIRTUAL_TOKENS;
-        token_state.real_sol_reserve = 0;
-        token_state.real_token_reserve = TOTAL_SUPPLY;
-        token_state.total_burned = 0;
-        token_state.volume = 0;
-        token_state.creator_fees_accrued = 0;
-        token_state.is_graduated = false;
-        token_state.is_paused = false;
-        token_state.created_at = Clock::get()?.unix_timestamp;
-        token_state.bump = ctx.bumps.token_state;
-        token_state.vault_bump = ctx.bumps.sol_vault;
-
-        let bump = token_state.bump;
-let mint_key = ctx.accounts.mint.key();
-
-let seeds = &[
-    b"token_state",
-    mint_key.as_ref(),
-    &[bump],
-];
-let signer_seeds = &[&seeds[..]];
-
-let cpi_accounts = MintTo {
-    mint: ctx.accounts.mint.to_account_info(),
-    to: ctx.accounts.token_vault.to_account_info(),
-    authority: token_state.to_account_info(),
-};
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-        token::mint_to(cpi_ctx, TOTAL_SUPPLY)?;
+        emit!(LaunchCommitted {
+            creator: launch_commitment.creator,
+            commitment,
+            committed_slot: launch_commitment.committed_slot,
+        });
 
-        ctx.accounts.config.token_count += 1;
+        Ok(())
+    }
 
-        if initial_buy_sol > 0 {
-            execute_buy_internal(
-                token_state,
-                &ctx.accounts.creator,
-                &ctx.accounts.creator_token_account,
-                &ctx.accounts.token_vault,
-                &ctx.accounts.sol_vault,
-                &ctx.accounts.platform_fee_recipient,
-                &ctx.accounts.token_program,
-                &ctx.accounts.system_program,
-                initial_buy_sol,
-                0,
-            )?;
-        }
+    pub fn reveal_launch(ctx: Context<CreateToken>, params: LaunchParams, salt: [u8; 32]) -> Result<()> {
+        check_deadline(params.deadline_unix_ts, params.max_slot)?;
+        let launch_commitment = ctx
+            .accounts
+            .launch_commitment
+            .as_ref()
+            .ok_or(BonkrError::InvalidLaunchCommitment)?;
+        let committed_slot = launch_commitment.committed_slot;
+        let slot = Clock::get()?.slot;
+        require!(
+            slot > committed_slot && slot - committed_slot <= LAUNCH_REVEAL_WINDOW_SLOTS,
+            BonkrError::RevealWindowClosed
+        );
+        require!(
+            launch_commitment_hash(&params.name, &params.symbol, &params.uri, &salt, &ctx.accounts.creator.key())
+                == launch_commitment.commitment,
+            BonkrError::InvalidLaunchCommitment
+        );
 
-        emit!(TokenCreated {
-            mint: ctx.accounts.mint.key(),
-            creator: ctx.accounts.creator.key(),
-            name: token_state.name.clone(),
-            symbol: token_state.symbol.clone(),
-            initial_buy_sol,
-        });
+        create_token_internal(ctx, params, Some(committed_slot))
+    }
+
+    pub fn cancel_launch_commitment(_ctx: Context<CancelLaunchCommitment>) -> Result<()> {
+        Ok(())
+    }
 
+    pub fn init_token_2022_mint(_ctx: Context<InitToken2022Mint>) -> Result<()> {
         Ok(())
     }
 
@@ -138,26 +220,120 @@ let cpi_accounts = MintTo {
         ctx: Context<Trade>,
         sol_amount: u64,
         min_tokens_out: u64,
+        allowlist_proof: Option<AllowlistProof>,
+        deadline_unix_ts: Option<i64>,
+        max_slot: Option<u64>,
     ) -> Result<()> {
-        require!(!ctx.accounts.config.is_paused, BonkrError::FactoryPaused);
-        require!(!ctx.accounts.token_state.is_paused, BonkrError::TokenPaused);
+        check_deadline(deadline_unix_ts, max_slot)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            factory_pause_flags(&ctx.accounts.config, now) & PAUSE_BUY == 0,
+            BonkrError::FactoryPaused
+        );
+        require!(
+            token_pause_flags(&ctx.accounts.token_state, now) & PAUSE_BUY == 0,
+            BonkrError::TokenPaused
+        );
         require!(!ctx.accounts.token_state.is_graduated, BonkrError::TokenGraduated);
         require!(sol_amount > 0, BonkrError::InvalidAmount);
+        if now < ctx.accounts.token_state.trading_starts_at {
+            require!(
+                ctx.accounts.token_state.creator_prelaunch_buy
+                    && ctx.accounts.user.key() == ctx.accounts.token_state.creator,
+                BonkrError::TradingNotStarted
+            );
+        }
+
+        let allowlist_cap = if allowlist_active(&ctx.accounts.token_state, now) {
+            let allowlist_proof = allowlist_proof.ok_or(BonkrError::NotAllowlisted)?;
+            require!(
+                verify_allowlist_proof(
+                    &ctx.accounts.token_state.allowlist_root,
+                    &ctx.accounts.user.key(),
+                    &allowlist_proof,
+                ),
+                BonkrError::NotAllowlisted
+            );
+            Some(allowlist_proof.max_tokens)
+        } else {
+            None
+        };
+
+        let slot = Clock::get()?.slot;
+        require!(
+            slot >= ctx.accounts.token_state.breaker_cooldown_until,
+            BonkrError::CircuitBreakerCooldown
+        );
+
+        // Buys that would overrun the curve or the graduation market cap are
+        // filled up to the boundary; the unused SOL is never taken from the
+        // buyer and the slippage bound is scaled down with the fill.
+        let requested_sol_amount = sol_amount;
+        let sol_amount = sol_amount.min(max_fillable_sol(&ctx.accounts.config, &ctx.accounts.token_state));
+        require!(sol_amount > 0, BonkrError::InsufficientTokens);
+        let min_tokens_out = (min_tokens_out as u128 * sol_amount as u128 / requested_sol_amount as u128) as u64;
+
+        let price_before = curve_price(
+            ctx.accounts.token_state.virtual_sol_reserve,
+            ctx.accounts.token_state.virtual_token_reserve,
+        );
+
+        init_user_position(
+            &mut ctx.accounts.user_position,
+            ctx.accounts.mint.key(),
+            ctx.accounts.user.key(),
+            ctx.bumps.user_position,
+        );
+
+        enforce_trade_guard(
+            &ctx.accounts.config,
+            &ctx.accounts.token_state,
+            &mut ctx.accounts.trade_guard,
+            TradeGuardCheck {
+                user: ctx.accounts.user.key(),
+                bump: ctx.bumps.trade_guard,
+                is_buy: true,
+                now,
+                slot,
+            },
+        )?;
 
         execute_buy_internal(
+            &ctx.accounts.config,
             &mut ctx.accounts.token_state,
-            &ctx.accounts.user,
-            &ctx.accounts.user_token_account,
-            &ctx.accounts.token_vault,
-            &ctx.accounts.sol_vault,
-            &ctx.accounts.platform_fee_recipient,
-            &ctx.accounts.token_program,
-            &ctx.accounts.system_program,
-            sol_amount,
-            min_tokens_out,
+            &mut ctx.accounts.user_position,
+            BuyAccounts {
+                user: &ctx.accounts.user,
+                mint: &ctx.accounts.mint,
+                user_token_account: &ctx.accounts.user_token_account,
+                token_vault: &ctx.accounts.token_vault,
+                sol_vault: &ctx.accounts.sol_vault,
+                platform_fee_recipient: &ctx.accounts.platform_fee_recipient,
+                token_program: &ctx.accounts.token_program,
+                system_program: &ctx.accounts.system_program,
+            },
+            BuyOrder {
+                sol_amount,
+                requested_sol_amount,
+                min_tokens_out,
+            },
         )?;
 
-        check_graduation(&mut ctx.accounts.token_state, ctx.accounts.config.sol_price_usd)?;
+        if let Some(max_tokens) = allowlist_cap.filter(|max_tokens| *max_tokens > 0) {
+            require!(
+                ctx.accounts.user_position.tokens_bought <= max_tokens,
+                BonkrError::AllowlistCapExceeded
+            );
+        }
+
+        // Creator buys before trading opens count against the same cap as
+        // the initial buy in `create_token`.
+        if now < ctx.accounts.token_state.trading_starts_at {
+            check_dev_buy_cap(&ctx.accounts.config, ctx.accounts.user_position.tokens_bought)?;
+        }
+
+        update_circuit_breaker(&ctx.accounts.config, &mut ctx.accounts.token_state, price_before, slot);
+        check_graduation(&mut ctx.accounts.token_state, &ctx.accounts.config)?;
 
         Ok(())
     }
@@ -166,11 +342,35 @@ let cpi_accounts = MintTo {
         ctx: Context<Trade>,
         token_amount: u64,
         min_sol_out: u64,
+        deadline_unix_ts: Option<i64>,
+        max_slot: Option<u64>,
     ) -> Result<()> {
-        require!(!ctx.accounts.config.is_paused, BonkrError::FactoryPaused);
-        require!(!ctx.accounts.token_state.is_paused, BonkrError::TokenPaused);
+        check_deadline(deadline_unix_ts, max_slot)?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            factory_pause_flags(&ctx.accounts.config, now) & PAUSE_SELL == 0,
+            BonkrError::FactoryPaused
+        );
+        require!(
+            token_pause_flags(&ctx.accounts.token_state, now) & PAUSE_SELL == 0,
+            BonkrError::TokenPaused
+        );
         require!(!ctx.accounts.token_state.is_graduated, BonkrError::TokenGraduated);
         require!(token_amount > 0, BonkrError::InvalidAmount);
+        require!(now >= ctx.accounts.token_state.trading_starts_at, BonkrError::TradingNotStarted);
+
+        init_user_position(
+            &mut ctx.accounts.user_position,
+            ctx.accounts.mint.key(),
+            ctx.accounts.user.key(),
+            ctx.bumps.user_position,
+        );
+
+        let slot = Clock::get()?.slot;
+        require!(
+            slot >= ctx.accounts.token_state.breaker_cooldown_until,
+            BonkrError::CircuitBreakerCooldown
+        );
 
         let token_state = &mut ctx.accounts.token_state;
 
@@ -178,23 +378,43 @@ let cpi_accounts = MintTo {
         let new_token_reserve = token_state.virtual_token_reserve + token_amount;
         let new_sol_reserve = (k / new_token_reserve as u128) as u64;
         let sol_out_gross = token_state.virtual_sol_reserve - new_sol_reserve;
+        let price_before = curve_price(token_state.virtual_sol_reserve, token_state.virtual_token_reserve);
+
+        enforce_trade_guard(
+            &ctx.accounts.config,
+            token_state,
+            &mut ctx.accounts.trade_guard,
+            TradeGuardCheck {
+                user: ctx.accounts.user.key(),
+                bump: ctx.bumps.trade_guard,
+                is_buy: false,
+                now,
+                slot,
+            },
+        )?;
 
         require!(sol_out_gross <= token_state.real_sol_reserve, BonkrError::InsufficientLiquidity);
 
         let platform_fee = (sol_out_gross * PLATFORM_FEE_BP) / BP_DENOMINATOR;
         let creator_fee = (sol_out_gross * CREATOR_FEE_BP) / BP_DENOMINATOR;
-        let burn_fee_tokens = (token_amount * BURN_FEE_BP) / BP_DENOMINATOR;
+        let burn_fee_tokens = burn_fee(token_amount);
         let sol_to_seller = sol_out_gross - (sol_out_gross * TOTAL_FEE_BP) / BP_DENOMINATOR;
 
         require!(sol_to_seller >= min_sol_out, BonkrError::SlippageExceeded);
+        check_price_impact(&ctx.accounts.config, token_state, new_sol_reserve, new_token_reserve)?;
 
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.user_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
             to: ctx.accounts.token_vault.to_account_info(),
             authority: ctx.accounts.user.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
-        token::transfer(CpiContext::new(cpi_program, cpi_accounts), token_amount)?;
+        token_interface::transfer_checked(
+            CpiContext::new(cpi_program, cpi_accounts),
+            token_amount,
+            ctx.accounts.mint.decimals,
+        )?;
 
         if burn_fee_tokens > 0 {
             let seeds = &[
@@ -210,7 +430,7 @@ let cpi_accounts = MintTo {
                 authority: token_state.to_account_info(),
             };
             let cpi_program = ctx.accounts.token_program.to_account_info();
-            token::burn(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), burn_fee_tokens)?;
+            token_interface::burn(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), burn_fee_tokens)?;
             token_state.total_burned += burn_fee_tokens;
         }
 
@@ -221,12 +441,16 @@ let cpi_accounts = MintTo {
         token_state.volume += sol_out_gross;
         token_state.creator_fees_accrued += creator_fee;
 
+        record_sell(&mut ctx.accounts.user_position, token_amount, sol_to_seller, now);
+
         **ctx.accounts.sol_vault.to_account_info().try_borrow_mut_lamports()? -= platform_fee;
         **ctx.accounts.platform_fee_recipient.to_account_info().try_borrow_mut_lamports()? += platform_fee;
 
         **ctx.accounts.sol_vault.to_account_info().try_borrow_mut_lamports()? -= sol_to_seller;
         **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += sol_to_seller;
 
+        update_circuit_breaker(&ctx.accounts.config, token_state, price_before, slot);
+
         emit!(TokenSold {
             mint: token_state.mint,
             seller: ctx.accounts.user.key(),
@@ -234,7 +458,49 @@ let cpi_accounts = MintTo {
             sol_amount: sol_to_seller,
         });
 
-        check_graduation(token_state, ctx.accounts.config.sol_price_usd)?;
+        check_graduation(token_state, &ctx.accounts.config)?;
+
+        Ok(())
+    }
+
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let creator_vesting = &mut ctx.accounts.creator_vesting;
+        let now = Clock::get()?.unix_timestamp;
+        let amount = vested_amount(creator_vesting, now) - creator_vesting.claimed_amount;
+        require!(amount > 0, BonkrError::NothingVested);
+
+        creator_vesting.claimed_amount += amount;
+
+        let mint_key = creator_vesting.mint;
+        let seeds = &[
+            b"creator_vesting",
+            mint_key.as_ref(),
+            &[creator_vesting.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vesting_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.creator_token_account.to_account_info(),
+            authority: creator_vesting.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        let token_state = &mut ctx.accounts.token_state;
+        token_state.creator_locked_tokens = token_state.creator_locked_tokens.saturating_sub(amount);
+
+        emit!(VestedClaimed {
+            mint: mint_key,
+            creator: ctx.accounts.creator.key(),
+            amount,
+            remaining_locked: token_state.creator_locked_tokens,
+        });
 
         Ok(())
     }
@@ -246,6 +512,13 @@ let cpi_accounts = MintTo {
         require!(ctx.accounts.creator.key() == token_state.creator, BonkrError::NotCreator);
 
         token_state.creator_fees_accrued = 0;
+
+        let creator_profile = &mut ctx.accounts.creator_profile;
+        if creator_profile.creator == Pubkey::default() {
+            creator_profile.creator = ctx.accounts.creator.key();
+            creator_profile.bump = ctx.bumps.creator_profile;
+        }
+        creator_profile.total_creator_fees += amount;
         
         **ctx.accounts.sol_vault.to_account_info().try_borrow_mut_lamports()? -= amount;
         **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? += amount;
@@ -258,15 +531,28 @@ let cpi_accounts = MintTo {
         Ok(())
     }
 
-    pub fn admin_withdraw_lp(ctx: Context<AdminWithdrawLP>) -> Result<()> {
+    pub fn execute_withdraw_lp(ctx: Context<ExecuteWithdrawLP>) -> Result<()> {
+        let pending_action = &ctx.accounts.pending_action;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= pending_action.execute_at, BonkrError::TimelockNotElapsed);
+        match pending_action.action {
+            PendingActionKind::WithdrawLp { mint, recipient } => {
+                require!(mint == ctx.accounts.mint.key(), BonkrError::InvalidPendingAction);
+                require!(recipient == ctx.accounts.recipient.key(), BonkrError::InvalidPendingAction);
+            }
+            _ => return err!(BonkrError::InvalidPendingAction),
+        }
+
         let token_state = &mut ctx.accounts.token_state;
-        
+
         let sol_amount = token_state.real_sol_reserve;
         let token_amount = token_state.real_token_reserve;
 
         token_state.real_sol_reserve = 0;
         token_state.real_token_reserve = 0;
-        token_state.is_paused = true;
+        token_state.pause_flags = PAUSE_TRADING;
+        token_state.guardian_pause_flags = 0;
+        token_state.guardian_paused_until = 0;
 
         if sol_amount > 0 {
             **ctx.accounts.sol_vault.to_account_info().try_borrow_mut_lamports()? -= sol_amount;
@@ -281,13 +567,18 @@ let cpi_accounts = MintTo {
             ];
             let signer_seeds = &[&seeds[..]];
 
-            let cpi_accounts = Transfer {
+            let cpi_accounts = TransferChecked {
                 from: ctx.accounts.token_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
                 to: ctx.accounts.recipient_token_account.to_account_info(),
                 authority: token_state.to_account_info(),
             };
             let cpi_program = ctx.accounts.token_program.to_account_info();
-            token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), token_amount)?;
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
+                token_amount,
+                ctx.accounts.mint.decimals,
+            )?;
         }
 
         emit!(LPWithdrawn {
@@ -297,6 +588,20 @@ let cpi_accounts = MintTo {
             recipient: ctx.accounts.recipient.key(),
         });
 
+        emit!(PauseStateChanged {
+            mint: Some(token_state.mint),
+            pause_flags: PAUSE_TRADING,
+            guardian_pause_flags: 0,
+            guardian_paused_until: 0,
+            reason: PAUSE_REASON_LP_WITHDRAWN,
+            changed_by: ctx.accounts.executor.key(),
+        });
+
+        emit!(ActionExecuted {
+            id: pending_action.id,
+            action: pending_action.action.clone(),
+        });
+
         Ok(())
     }
 
@@ -313,174 +618,1839 @@ let cpi_accounts = MintTo {
         Ok(())
     }
 
-    pub fn pause_token(ctx: Context<AdminAction>, paused: bool) -> Result<()> {
-        ctx.accounts.token_state.is_paused = paused;
+    // Lets anyone graduate a token whose market cap crossed the threshold
+    // through an oracle price update rather than a trade. The optional bounty
+    // is paid from the platform's bounty pool and never touches the curve.
+    pub fn crank_graduation(ctx: Context<CrankGraduation>) -> Result<()> {
+        let token_state = &mut ctx.accounts.token_state;
+        require!(!token_state.is_graduated, BonkrError::AlreadyGraduated);
+        check_graduation(token_state, &ctx.accounts.config)?;
+        require!(token_state.is_graduated, BonkrError::GraduationThresholdNotMet);
+
+        let bounty = match &ctx.accounts.bounty_pool {
+            Some(bounty_pool) => {
+                let bounty_pool = bounty_pool.to_account_info();
+                let available = bounty_pool
+                    .lamports()
+                    .saturating_sub(Rent::get()?.minimum_balance(bounty_pool.data_len()));
+                let bounty = ctx.accounts.config.graduation_bounty.min(available);
+                if bounty > 0 {
+                    **bounty_pool.try_borrow_mut_lamports()? -= bounty;
+                    **ctx.accounts.caller.to_account_info().try_borrow_mut_lamports()? += bounty;
+                }
+                bounty
+            }
+            None => 0,
+        };
+
+        emit!(GraduationCranked {
+            mint: token_state.mint,
+            caller: ctx.accounts.caller.key(),
+            bounty,
+        });
+
         Ok(())
     }
 
-    pub fn pause_factory(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
-        ctx.accounts.config.is_paused = paused;
+    pub fn fund_bounty_pool(ctx: Context<FundBountyPool>, amount: u64) -> Result<()> {
+        require!(amount > 0, BonkrError::InvalidAmount);
+        ctx.accounts.bounty_pool.bump = ctx.bumps.bounty_pool;
+
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.funder.to_account_info(),
+                to: ctx.accounts.bounty_pool.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, amount)?;
+
+        emit!(BountyPoolFunded {
+            funder: ctx.accounts.funder.key(),
+            amount,
+        });
+
         Ok(())
     }
 
-    pub fn set_creation_fee(ctx: Context<UpdateConfig>, fee: u64) -> Result<()> {
-        ctx.accounts.config.creation_fee = fee;
+    pub fn withdraw_bounty_pool(ctx: Context<WithdrawBountyPool>, amount: u64) -> Result<()> {
+        let bounty_pool = ctx.accounts.bounty_pool.to_account_info();
+        let available = bounty_pool
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(bounty_pool.data_len()));
+        require!(amount > 0 && amount <= available, BonkrError::NoFundsToWithdraw);
+
+        **bounty_pool.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        emit!(BountyPoolWithdrawn {
+            authority: ctx.accounts.authority.key(),
+            amount,
+        });
+
         Ok(())
     }
 
-    pub fn set_platform_fee_recipient(ctx: Context<UpdateConfig>, recipient: Pubkey) -> Result<()> {
-        ctx.accounts.config.platform_fee_recipient = recipient;
-        Ok(())
+    pub fn get_market_cap(ctx: Context<ViewMarketCap>) -> Result<u64> {
+        // Saturate rather than truncate so the view never reports less than
+        // the value `check_graduation` compares against the threshold.
+        Ok(u64::try_from(market_cap_usd(&ctx.accounts.config, &ctx.accounts.token_state)).unwrap_or(u64::MAX))
     }
 
-    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<()> {
-        let balance = ctx.accounts.sol_vault.to_account_info().lamports();
-        let rent = Rent::get()?.minimum_balance(0);
-        let withdrawable = balance.saturating_sub(rent);
-        
-        require!(withdrawable > 0, BonkrError::NoFundsToWithdraw);
+    pub fn pause_token(ctx: Context<AdminAction>, pause_flags: u8, reason: u8) -> Result<()> {
+        require!(pause_flags & !PAUSE_TRADING == 0, BonkrError::InvalidPauseFlags);
 
-        **ctx.accounts.sol_vault.to_account_info().try_borrow_mut_lamports()? -= withdrawable;
-        **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += withdrawable;
+        let token_state = &mut ctx.accounts.token_state;
+        token_state.pause_flags = pause_flags;
+        token_state.guardian_pause_flags = 0;
+        token_state.guardian_paused_until = 0;
 
-        emit!(EmergencyWithdrawEvent { amount: withdrawable });
+        emit!(PauseStateChanged {
+            mint: Some(token_state.mint),
+            pause_flags,
+            guardian_pause_flags: 0,
+            guardian_paused_until: 0,
+            reason,
+            changed_by: ctx.accounts.authority.key(),
+        });
 
         Ok(())
     }
-}
 
-fn execute_buy_internal<'info>(
-    token_state: &mut Account<'info, TokenState>,
-    user: &Signer<'info>,
-    user_token_account: &Account<'info, TokenAccount>,
-    token_vault: &Account<'info, TokenAccount>,
-    sol_vault: &AccountInfo<'info>,
-    platform_fee_recipient: &AccountInfo<'info>,
-    token_program: &Program<'info, Token>,
-    system_program: &Program<'info, anchor_lang::system_program::System>,
-    sol_amount: u64,
-    min_tokens_out: u64,
-) -> Result<()> {
-    let platform_fee = (sol_amount * PLATFORM_FEE_BP) / BP_DENOMINATOR;
-    let creator_fee = (sol_amount * CREATOR_FEE_BP) / BP_DENOMINATOR;
-    let sol_to_reserve = sol_amount - platform_fee - creator_fee;
+    pub fn pause_factory(ctx: Context<UpdateConfig>, pause_flags: u8, reason: u8) -> Result<()> {
+        require!(pause_flags & !PAUSE_ALL == 0, BonkrError::InvalidPauseFlags);
 
-    let k = (token_state.virtual_sol_reserve as u128) * (token_state.virtual_token_reserve as u128);
-    let new_sol_reserve = token_state.virtual_sol_reserve + sol_to_reserve;
-    let new_token_reserve = (k / new_sol_reserve as u128) as u64;
-    let tokens_out = token_state.virtual_token_reserve - new_token_reserve;
+        let config = &mut ctx.accounts.config;
+        config.pause_flags = pause_flags;
+        config.guardian_pause_flags = 0;
+        config.guardian_paused_until = 0;
 
-    require!(tokens_out >= min_tokens_out, BonkrError::SlippageExceeded);
-    require!(tokens_out <= token_state.real_token_reserve, BonkrError::InsufficientTokens);
+        emit!(PauseStateChanged {
+            mint: None,
+            pause_flags,
+            guardian_pause_flags: 0,
+            guardian_paused_until: 0,
+            reason,
+            changed_by: ctx.accounts.authority.key(),
+        });
 
-    let burn_amount = (tokens_out * BURN_FEE_BP) / BP_DENOMINATOR;
-    let tokens_to_buyer = tokens_out - burn_amount;
+        Ok(())
+    }
 
-    let cpi_context = CpiContext::new(
-        system_program.to_account_info(),
-        anchor_lang::system_program::Transfer {
-            from: user.to_account_info(),
-            to: sol_vault.to_account_info(),
-        },
-    );
-    anchor_lang::system_program::transfer(cpi_context, sol_to_reserve + creator_fee)?;
+    pub fn guardian_pause_factory(
+        ctx: Context<GuardianConfig>,
+        pause_flags: u8,
+        reason: u8,
+    ) -> Result<()> {
+        require!(pause_flags != 0, BonkrError::InvalidPauseFlags);
+        require!(pause_flags & !PAUSE_ALL == 0, BonkrError::InvalidPauseFlags);
 
-    let cpi_context = CpiContext::new(
-        system_program.to_account_info(),
-        anchor_lang::system_program::Transfer {
-            from: user.to_account_info(),
-            to: platform_fee_recipient.to_account_info(),
-        },
-    );
-    anchor_lang::system_program::transfer(cpi_context, platform_fee)?;
+        let config = &mut ctx.accounts.config;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= config.guardian_pause_cooldown_until, BonkrError::GuardianPauseCooldown);
+        config.guardian_pause_flags = pause_flags;
+        config.guardian_paused_until = now.saturating_add(config.guardian_pause_duration);
+        config.guardian_pause_cooldown_until =
+            config.guardian_paused_until.saturating_add(config.guardian_pause_duration);
 
-    let seeds = &[
-        b"token_state",
-        token_state.mint.as_ref(),
-        &[token_state.bump],
-    ];
-    let signer_seeds = &[&seeds[..]];
+        emit!(PauseStateChanged {
+            mint: None,
+            pause_flags: config.pause_flags,
+            guardian_pause_flags: config.guardian_pause_flags,
+            guardian_paused_until: config.guardian_paused_until,
+            reason,
+            changed_by: ctx.accounts.guardian.key(),
+        });
 
-    let cpi_accounts = Transfer {
-        from: token_vault.to_account_info(),
-        to: user_token_account.to_account_info(),
-        authority: token_state.to_account_info(),
-    };
-    let cpi_program = token_program.to_account_info();
-    token::transfer(CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds), tokens_to_buyer)?;
+        Ok(())
+    }
 
-    token_state.virtual_sol_reserve = new_sol_reserve;
-    token_state.virtual_token_reserve = new_token_reserve;
-    token_state.real_sol_reserve += sol_to_reserve + creator_fee;
-    token_state.real_token_reserve -= tokens_out;
-    token_state.total_burned += burn_amount;
-    token_state.volume += sol_amount;
-    token_state.creator_fees_accrued += creator_fee;
+    pub fn guardian_pause_token(
+        ctx: Context<GuardianAction>,
+        pause_flags: u8,
+        reason: u8,
+    ) -> Result<()> {
+        require!(pause_flags != 0, BonkrError::InvalidPauseFlags);
+        require!(pause_flags & !PAUSE_TRADING == 0, BonkrError::InvalidPauseFlags);
 
-    emit!(TokenBought {
-        mint: token_state.mint,
-        buyer: user.key(),
-        sol_amount,
-        token_amount: tokens_to_buyer,
-    });
+        let now = Clock::get()?.unix_timestamp;
+        let duration = ctx.accounts.config.guardian_pause_duration;
+        let token_state = &mut ctx.accounts.token_state;
+        require!(now >= token_state.guardian_pause_cooldown_until, BonkrError::GuardianPauseCooldown);
+        token_state.guardian_pause_flags = pause_flags;
+        token_state.guardian_paused_until = now.saturating_add(duration);
+        token_state.guardian_pause_cooldown_until = token_state.guardian_paused_until.saturating_add(duration);
 
-    Ok(())
-}
+        emit!(PauseStateChanged {
+            mint: Some(token_state.mint),
+            pause_flags: token_state.pause_flags,
+            guardian_pause_flags: token_state.guardian_pause_flags,
+            guardian_paused_until: token_state.guardian_paused_until,
+            reason,
+            changed_by: ctx.accounts.guardian.key(),
+        });
 
-fn check_graduation(token_state: &mut Account<TokenState>, sol_price_usd: u64) -> Result<()> {
-    if token_state.is_graduated {
-        return Ok(());
+        Ok(())
     }
 
-    let market_cap_sol = (token_state.virtual_sol_reserve as u128 * TOTAL_SUPPLY as u128) 
-        / token_state.virtual_token_reserve as u128;
-    
-    let market_cap_usd = (market_cap_sol * sol_price_usd as u128) / 1_000_000_000;
+    pub fn confirm_factory_pause(ctx: Context<UpdateConfig>, reason: u8) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let now = Clock::get()?.unix_timestamp;
+        require!(config.guardian_paused_until > now, BonkrError::NoGuardianPause);
+        config.pause_flags |= config.guardian_pause_flags;
+        config.guardian_pause_flags = 0;
+        config.guardian_paused_until = 0;
 
-    if market_cap_usd >= GRADUATION_USD as u128 {
-        token_state.is_graduated = true;
-        emit!(TokenGraduated {
-            mint: token_state.mint,
-            market_cap_usd: market_cap_usd as u64,
+        emit!(PauseStateChanged {
+            mint: None,
+            pause_flags: config.pause_flags,
+            guardian_pause_flags: 0,
+            guardian_paused_until: 0,
+            reason,
+            changed_by: ctx.accounts.authority.key(),
         });
+
+        Ok(())
     }
 
-    Ok(())
-}
+    pub fn confirm_token_pause(ctx: Context<AdminAction>, reason: u8) -> Result<()> {
+        let token_state = &mut ctx.accounts.token_state;
+        let now = Clock::get()?.unix_timestamp;
+        require!(token_state.guardian_paused_until > now, BonkrError::NoGuardianPause);
+        token_state.pause_flags |= token_state.guardian_pause_flags;
+        token_state.guardian_pause_flags = 0;
+        token_state.guardian_paused_until = 0;
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + GlobalConfig::INIT_SPACE,
-        seeds = [b"config"],
-        bump
-    )]
-    pub config: Account<'info, GlobalConfig>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    /// CHECK: This is the platform fee recipient account
-    pub platform_fee_recipient: AccountInfo<'info>,
-    pub system_program: Program<'info, anchor_lang::system_program::System>,
-}
+        emit!(PauseStateChanged {
+            mint: Some(token_state.mint),
+            pause_flags: token_state.pause_flags,
+            guardian_pause_flags: 0,
+            guardian_paused_until: 0,
+            reason,
+            changed_by: ctx.accounts.authority.key(),
+        });
 
-#[derive(Accounts)]
-pub struct CreateToken<'info> {
-    #[account(
-        mut,
-        seeds = [b"config"],
-        bump = config.bump
-    )]
-    pub config: Account<'info, GlobalConfig>,
-    
-    #[account(
-        init,
-        payer = creator,
+        Ok(())
+    }
+
+    pub fn update_metadata(
+        ctx: Context<UpdateMetadata>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        let token_state = &mut ctx.accounts.token_state;
+        require!(!token_state.is_graduated, BonkrError::TokenGraduated);
+        require!(!token_state.metadata_locked, BonkrError::MetadataLocked);
+        validate_metadata(&name, &symbol, &uri)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        if launch_name_key(&name) != launch_name_key(&token_state.name) {
+            claim_launch_identity(
+                &mut ctx.accounts.name_claim,
+                token_state.mint,
+                None,
+                now,
+                ctx.bumps.name_claim,
+            )?;
+        }
+        if normalize_symbol(&symbol) != normalize_symbol(&token_state.symbol) {
+            claim_launch_identity(
+                &mut ctx.accounts.symbol_claim,
+                token_state.mint,
+                None,
+                now,
+                ctx.bumps.symbol_claim,
+            )?;
+        }
+
+        // A ticker change moves the registry entry: the new normalized symbol
+        // must be free, and the record held under the old one is released.
+        let normalized_symbol = normalize_symbol(&symbol);
+        if normalized_symbol != normalize_symbol(&token_state.symbol) {
+            if ctx.accounts.config.symbol_uniqueness {
+                let symbol_record = ctx
+                    .accounts
+                    .symbol_record
+                    .as_mut()
+                    .ok_or(BonkrError::MissingSymbolRecord)?;
+                require!(symbol_record.symbol.is_empty(), BonkrError::SymbolTaken);
+                symbol_record.symbol = normalized_symbol;
+                symbol_record.mint = token_state.mint;
+                symbol_record.owner = ctx.accounts.creator.key();
+                symbol_record.reserved = false;
+                symbol_record.created_at = Clock::get()?.unix_timestamp;
+                symbol_record.created_slot = Clock::get()?.slot;
+                symbol_record.bump = ctx.bumps.symbol_record.ok_or(BonkrError::MissingSymbolRecord)?;
+            }
+            release_symbol_record(
+                &ctx.accounts.old_symbol_record,
+                token_state.mint,
+                &ctx.accounts.creator.to_account_info(),
+            )?;
+        }
+
+        token_state.name = name;
+        token_state.symbol = symbol;
+        token_state.uri = uri;
+
+        if *ctx.accounts.mint.to_account_info().owner == spl_token_2022::ID {
+            let seeds = &[
+                b"token_state",
+                token_state.mint.as_ref(),
+                &[token_state.bump],
+            ];
+            let signer_seeds = &[&seeds[..]];
+
+            sync_token_metadata(
+                token_state,
+                &ctx.accounts.mint,
+                &ctx.accounts.creator,
+                &ctx.accounts.token_program,
+                &ctx.accounts.system_program,
+                signer_seeds,
+            )?;
+        }
+
+        emit!(MetadataUpdated {
+            mint: token_state.mint,
+            name: token_state.name.clone(),
+            symbol: token_state.symbol.clone(),
+            uri: token_state.uri.clone(),
+        });
+
+        Ok(())
+    }
+
+    pub fn set_token_profile(ctx: Context<SetTokenProfile>, profile: TokenProfileParams) -> Result<()> {
+        require!(!ctx.accounts.token_state.is_graduated, BonkrError::TokenGraduated);
+        require!(!ctx.accounts.token_state.metadata_locked, BonkrError::MetadataLocked);
+        validate_token_profile(&profile)?;
+        let now = Clock::get()?.unix_timestamp;
+        write_token_profile(
+            &mut ctx.accounts.token_profile,
+            ctx.accounts.token_state.mint,
+            profile,
+            now,
+            ctx.bumps.token_profile,
+        );
+        Ok(())
+    }
+
+    pub fn lock_metadata(ctx: Context<CreatorAction>) -> Result<()> {
+        let token_state = &mut ctx.accounts.token_state;
+        require!(!token_state.metadata_locked, BonkrError::MetadataLocked);
+        token_state.metadata_locked = true;
+
+        emit!(MetadataLocked {
+            mint: token_state.mint,
+        });
+
+        Ok(())
+    }
+
+    // Only usable while a scheduled start is still pending. Tokens that open
+    // for trading immediately pass the allowlist in `LaunchParams` instead.
+    pub fn set_allowlist(
+        ctx: Context<CreatorAction>,
+        root: [u8; 32],
+        ends_at: i64,
+    ) -> Result<()> {
+        let token_state = &mut ctx.accounts.token_state;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < token_state.trading_starts_at, BonkrError::TradingAlreadyStarted);
+        validate_allowlist_phase(token_state.trading_starts_at, ends_at)?;
+
+        token_state.allowlist_root = root;
+        token_state.allowlist_ends_at = ends_at;
+
+        emit!(AllowlistSet {
+            mint: token_state.mint,
+            root,
+            ends_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_circuit_breaker(
+        ctx: Context<UpdateConfig>,
+        threshold_bp: u64,
+        window_slots: u64,
+        cooldown_slots: u64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.breaker_threshold_bp = threshold_bp;
+        config.breaker_window_slots = window_slots;
+        config.breaker_cooldown_slots = cooldown_slots;
+        Ok(())
+    }
+
+    pub fn set_wallet_cap(
+        ctx: Context<UpdateConfig>,
+        max_wallet_buy_bp: u64,
+        wallet_cap_duration: i64,
+    ) -> Result<()> {
+        require!(max_wallet_buy_bp <= BP_DENOMINATOR, BonkrError::InvalidAmount);
+        require!(wallet_cap_duration >= 0, BonkrError::InvalidAmount);
+        let config = &mut ctx.accounts.config;
+        config.max_wallet_buy_bp = max_wallet_buy_bp;
+        config.wallet_cap_duration = wallet_cap_duration;
+        Ok(())
+    }
+
+    pub fn set_symbol_uniqueness(ctx: Context<UpdateConfig>, enabled: bool) -> Result<()> {
+        ctx.accounts.config.symbol_uniqueness = enabled;
+        Ok(())
+    }
+
+    pub fn reserve_symbol(ctx: Context<ReserveSymbol>, symbol: String) -> Result<()> {
+        validate_symbol(&symbol)?;
+
+        let symbol_record = &mut ctx.accounts.symbol_record;
+        symbol_record.symbol = normalize_symbol(&symbol);
+        symbol_record.mint = Pubkey::default();
+        symbol_record.owner = ctx.accounts.authority.key();
+        symbol_record.reserved = true;
+        symbol_record.created_at = Clock::get()?.unix_timestamp;
+        symbol_record.created_slot = Clock::get()?.slot;
+        symbol_record.bump = ctx.bumps.symbol_record;
+
+        emit!(SymbolReserved {
+            symbol: symbol_record.symbol.clone(),
+        });
+
+        Ok(())
+    }
+
+    pub fn release_symbol(ctx: Context<ReleaseSymbol>) -> Result<()> {
+        emit!(SymbolReleased {
+            symbol: ctx.accounts.symbol_record.symbol.clone(),
+            mint: ctx.accounts.symbol_record.mint,
+        });
+        Ok(())
+    }
+
+    pub fn set_launch_limits(
+        ctx: Context<UpdateConfig>,
+        creator_launch_cooldown: i64,
+        max_launches_per_day: u32,
+    ) -> Result<()> {
+        require!(creator_launch_cooldown >= 0, BonkrError::InvalidAmount);
+        let config = &mut ctx.accounts.config;
+        config.creator_launch_cooldown = creator_launch_cooldown;
+        config.max_launches_per_day = max_launches_per_day;
+        Ok(())
+    }
+
+    pub fn set_sandwich_guard(
+        ctx: Context<UpdateConfig>,
+        sandwich_guard_slots: u64,
+        sandwich_guard_duration: i64,
+    ) -> Result<()> {
+        require!(sandwich_guard_slots <= MAX_SANDWICH_GUARD_SLOTS, BonkrError::InvalidAmount);
+        require!(sandwich_guard_duration >= 0, BonkrError::InvalidAmount);
+        let config = &mut ctx.accounts.config;
+        config.sandwich_guard_slots = sandwich_guard_slots;
+        config.sandwich_guard_duration = sandwich_guard_duration;
+        Ok(())
+    }
+
+    pub fn set_max_price_impact_bp(ctx: Context<UpdateConfig>, max_price_impact_bp: u64) -> Result<()> {
+        ctx.accounts.config.max_price_impact_bp = max_price_impact_bp;
+        Ok(())
+    }
+
+    pub fn set_graduation_bounty(ctx: Context<UpdateConfig>, graduation_bounty: u64) -> Result<()> {
+        require!(graduation_bounty <= MAX_GRADUATION_BOUNTY, BonkrError::InvalidAmount);
+        ctx.accounts.config.graduation_bounty = graduation_bounty;
+        Ok(())
+    }
+
+    pub fn set_market_cap_basis(ctx: Context<UpdateConfig>, market_cap_basis: MarketCapBasis) -> Result<()> {
+        ctx.accounts.config.market_cap_basis = market_cap_basis;
+        Ok(())
+    }
+
+    pub fn set_pow_difficulty(ctx: Context<UpdateConfig>, pow_difficulty: u8) -> Result<()> {
+        require!(pow_difficulty <= MAX_POW_DIFFICULTY, BonkrError::InvalidAmount);
+        ctx.accounts.config.pow_difficulty = pow_difficulty;
+        Ok(())
+    }
+
+    pub fn set_max_dev_buy_bp(ctx: Context<UpdateConfig>, max_dev_buy_bp: u64) -> Result<()> {
+        require!(max_dev_buy_bp <= BP_DENOMINATOR, BonkrError::InvalidAmount);
+        ctx.accounts.config.max_dev_buy_bp = max_dev_buy_bp;
+        Ok(())
+    }
+
+    pub fn set_guardian_pause_duration(ctx: Context<UpdateConfig>, duration: i64) -> Result<()> {
+        require!(duration > 0, BonkrError::InvalidAmount);
+        ctx.accounts.config.guardian_pause_duration = duration;
+        Ok(())
+    }
+
+    pub fn schedule_action(
+        ctx: Context<ScheduleAction>,
+        action: PendingActionKind,
+        execute_at: i64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            execute_at >= now.saturating_add(config.timelock_delay),
+            BonkrError::TimelockTooShort
+        );
+        if let PendingActionKind::SetTimelockDelay { delay } = action {
+            require!(delay >= 0, BonkrError::InvalidAmount);
+        }
+
+        let pending_action = &mut ctx.accounts.pending_action;
+        pending_action.id = config.action_count;
+        pending_action.action = action.clone();
+        pending_action.scheduled_at = now;
+        pending_action.execute_at = execute_at;
+        pending_action.bump = ctx.bumps.pending_action;
+
+        config.action_count += 1;
+
+        emit!(ActionScheduled {
+            id: pending_action.id,
+            action,
+            execute_at,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_action(ctx: Context<CancelAction>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now < ctx.accounts.pending_action.execute_at, BonkrError::TimelockElapsed);
+        // The guardian must not be able to veto its own replacement or keep
+        // the timelock it is checked by from changing.
+        if matches!(
+            ctx.accounts.pending_action.action,
+            PendingActionKind::SetGuardian { .. } | PendingActionKind::SetTimelockDelay { .. }
+        ) {
+            require!(
+                ctx.accounts.guardian.key() == ctx.accounts.config.authority,
+                BonkrError::Unauthorized
+            );
+        }
+
+        emit!(ActionCancelled {
+            id: ctx.accounts.pending_action.id,
+            cancelled_by: ctx.accounts.guardian.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn execute_config_action(ctx: Context<ExecuteConfigAction>) -> Result<()> {
+        let pending_action = &ctx.accounts.pending_action;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= pending_action.execute_at, BonkrError::TimelockNotElapsed);
+
+        let config = &mut ctx.accounts.config;
+        match pending_action.action {
+            PendingActionKind::SetPlatformFeeRecipient { recipient } => {
+                config.platform_fee_recipient = recipient;
+            }
+            PendingActionKind::SetCreationFee { fee } => {
+                config.creation_fee = fee;
+            }
+            PendingActionKind::SetGuardian { guardian } => {
+                config.guardian = guardian;
+            }
+            PendingActionKind::SetTimelockDelay { delay } => {
+                config.timelock_delay = delay;
+            }
+            PendingActionKind::WithdrawLp { .. } | PendingActionKind::EmergencyWithdraw { .. } => {
+                return err!(BonkrError::InvalidPendingAction);
+            }
+        }
+
+        emit!(ActionExecuted {
+            id: pending_action.id,
+            action: pending_action.action.clone(),
+        });
+
+        Ok(())
+    }
+
+    pub fn execute_emergency_withdraw(ctx: Context<ExecuteEmergencyWithdraw>) -> Result<()> {
+        let pending_action = &ctx.accounts.pending_action;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= pending_action.execute_at, BonkrError::TimelockNotElapsed);
+        match pending_action.action {
+            PendingActionKind::EmergencyWithdraw { mint, recipient } => {
+                require!(mint == ctx.accounts.token_state.mint, BonkrError::InvalidPendingAction);
+                require!(recipient == ctx.accounts.recipient.key(), BonkrError::InvalidPendingAction);
+            }
+            _ => return err!(BonkrError::InvalidPendingAction),
+        }
+
+        let balance = ctx.accounts.sol_vault.to_account_info().lamports();
+        let rent = Rent::get()?.minimum_balance(0);
+        let withdrawable = balance.saturating_sub(rent);
+        
+        require!(withdrawable > 0, BonkrError::NoFundsToWithdraw);
+
+        let token_state = &mut ctx.accounts.token_state;
+        token_state.real_sol_reserve = 0;
+        token_state.creator_fees_accrued = 0;
+        token_state.pause_flags = PAUSE_TRADING;
+        token_state.guardian_pause_flags = 0;
+        token_state.guardian_paused_until = 0;
+
+        **ctx.accounts.sol_vault.to_account_info().try_borrow_mut_lamports()? -= withdrawable;
+        **ctx.accounts.recipient.to_account_info().try_borrow_mut_lamports()? += withdrawable;
+
+        emit!(EmergencyWithdrawEvent {
+            mint: token_state.mint,
+            amount: withdrawable,
+            recipient: ctx.accounts.recipient.key(),
+        });
+
+        emit!(PauseStateChanged {
+            mint: Some(token_state.mint),
+            pause_flags: PAUSE_TRADING,
+            guardian_pause_flags: 0,
+            guardian_paused_until: 0,
+            reason: PAUSE_REASON_EMERGENCY_WITHDRAW,
+            changed_by: ctx.accounts.executor.key(),
+        });
+
+        emit!(ActionExecuted {
+            id: pending_action.id,
+            action: pending_action.action.clone(),
+        });
+
+        Ok(())
+    }
+}
+
+fn create_token_internal(
+    ctx: Context<CreateToken>,
+    params: LaunchParams,
+    launch_committed_slot: Option<u64>,
+) -> Result<()> {
+    let LaunchParams {
+        name,
+        symbol,
+        uri,
+        initial_buy_sol,
+        initial_min_tokens_out,
+        trading_starts_at,
+        creator_prelaunch_buy,
+        vesting,
+        profile,
+        pow,
+        allowlist,
+        ..
+    } = params;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        factory_pause_flags(&ctx.accounts.config, now) & PAUSE_CREATE == 0,
+        BonkrError::FactoryPaused
+    );
+    validate_metadata(&name, &symbol, &uri)?;
+    if let Some(profile) = &profile {
+        validate_token_profile(profile)?;
+    }
+    let trading_starts_at = trading_starts_at.unwrap_or(now);
+    require!(trading_starts_at >= now, BonkrError::InvalidTradingStart);
+    if let Some(allowlist) = &allowlist {
+        validate_allowlist_phase(trading_starts_at, allowlist.ends_at)?;
+    }
+    if let Some(vesting) = &vesting {
+        require!(initial_buy_sol > 0, BonkrError::InvalidVesting);
+        require!(
+            vesting.vesting_duration > 0
+                && vesting.cliff_duration >= 0
+                && vesting.cliff_duration <= vesting.vesting_duration,
+            BonkrError::InvalidVesting
+        );
+    }
+
+    let pow_difficulty = ctx.accounts.config.pow_difficulty;
+    if pow_difficulty > 0 {
+        let pow = pow.ok_or(BonkrError::InvalidProofOfWork)?;
+        let slot = Clock::get()?.slot;
+        require!(
+            pow.slot <= slot && slot - pow.slot <= MAX_POW_SLOT_AGE,
+            BonkrError::StaleProofOfWork
+        );
+        require!(
+            pow_leading_zeros(&ctx.accounts.creator.key(), &ctx.accounts.mint.key(), &pow) >= pow_difficulty as u32,
+            BonkrError::InvalidProofOfWork
+        );
+    }
+
+    claim_launch_identity(
+        &mut ctx.accounts.name_claim,
+        ctx.accounts.mint.key(),
+        launch_committed_slot,
+        now,
+        ctx.bumps.name_claim,
+    )?;
+    claim_launch_identity(
+        &mut ctx.accounts.symbol_claim,
+        ctx.accounts.mint.key(),
+        launch_committed_slot,
+        now,
+        ctx.bumps.symbol_claim,
+    )?;
+
+    record_launch(
+        &ctx.accounts.config,
+        &mut ctx.accounts.creator_profile,
+        ctx.accounts.creator.key(),
+        ctx.accounts.mint.key(),
+        now,
+        ctx.bumps.creator_profile,
+    )?;
+
+    let creation_fee = ctx.accounts.config.creation_fee;
+    if creation_fee > 0 {
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.creator.to_account_info(),
+                to: ctx.accounts.platform_fee_recipient.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, creation_fee)?;
+    }
+
+    let token_state = &mut ctx.accounts.token_state;
+    token_state.mint = ctx.accounts.mint.key();
+    token_state.creator = ctx.accounts.creator.key();
+    token_state.name = name;
+    token_state.symbol = symbol;
+    token_state.uri = uri;
+    token_state.virtual_sol_reserve = INITIAL_VIRTUAL_SOL;
+    token_state.virtual_token_reserve = INITIAL_VIRTUAL_TOKENS;
+    token_state.real_sol_reserve = 0;
+    token_state.real_token_reserve = TOTAL_SUPPLY;
+    token_state.total_burned = 0;
+    token_state.volume = 0;
+    token_state.creator_fees_accrued = 0;
+    token_state.is_graduated = false;
+    token_state.pause_flags = 0;
+    token_state.guardian_pause_flags = 0;
+    token_state.guardian_paused_until = 0;
+    token_state.breaker_reference_price = 0;
+    token_state.breaker_reference_slot = 0;
+    token_state.breaker_cooldown_until = 0;
+    token_state.created_at = now;
+    token_state.trading_starts_at = trading_starts_at;
+    token_state.creator_prelaunch_buy = creator_prelaunch_buy;
+    token_state.allowlist_root = allowlist.as_ref().map_or([0; 32], |allowlist| allowlist.root);
+    token_state.allowlist_ends_at = allowlist.as_ref().map_or(0, |allowlist| allowlist.ends_at);
+    token_state.creator_locked_tokens = 0;
+    token_state.creator_cliff_ends_at = 0;
+    token_state.creator_vesting_ends_at = 0;
+    token_state.metadata_locked = false;
+    token_state.guardian_pause_cooldown_until = 0;
+    token_state.bump = ctx.bumps.token_state;
+    token_state.vault_bump = ctx.bumps.sol_vault;
+    token_state.version = TOKEN_STATE_VERSION;
+
+    let bump = token_state.bump;
+    let mint_key = ctx.accounts.mint.key();
+
+    let seeds = &[
+        b"token_state",
+        mint_key.as_ref(),
+        &[bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = MintTo {
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.token_vault.to_account_info(),
+        authority: token_state.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+    token_interface::mint_to(cpi_ctx, TOTAL_SUPPLY)?;
+
+    if ctx.accounts.token_program.key() == spl_token_2022::ID {
+        validate_token_2022_mint(&ctx.accounts.mint.to_account_info(), &token_state.key())?;
+        initialize_token_metadata(
+            token_state,
+            &ctx.accounts.mint,
+            &ctx.accounts.creator,
+            &ctx.accounts.token_program,
+            &ctx.accounts.system_program,
+            signer_seeds,
+        )?;
+    }
+
+    let cpi_accounts = SetAuthority {
+        current_authority: token_state.to_account_info(),
+        account_or_mint: ctx.accounts.mint.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+    token_interface::set_authority(cpi_ctx, AuthorityType::MintTokens, None)?;
+
+    ctx.accounts.mint.reload()?;
+    assert_fixed_supply(&ctx.accounts.mint)?;
+
+    let token_index = &mut ctx.accounts.token_index;
+    token_index.index = ctx.accounts.config.token_count;
+    token_index.mint = mint_key;
+    token_index.creator = ctx.accounts.creator.key();
+    token_index.created_at = now;
+    token_index.bump = ctx.bumps.token_index;
+
+    ctx.accounts.config.token_count += 1;
+
+    init_user_position(
+        &mut ctx.accounts.creator_position,
+        mint_key,
+        ctx.accounts.creator.key(),
+        ctx.bumps.creator_position,
+    );
+
+    if initial_buy_sol > 0 {
+        let buyer_token_account = match (&vesting, &ctx.accounts.vesting_token_account) {
+            (Some(_), Some(vesting_token_account)) => vesting_token_account,
+            (Some(_), None) => return err!(BonkrError::MissingVestingAccounts),
+            (None, _) => &ctx.accounts.creator_token_account,
+        };
+
+        let tokens_bought = execute_buy_internal(
+            &ctx.accounts.config,
+            token_state,
+            &mut ctx.accounts.creator_position,
+            BuyAccounts {
+                user: &ctx.accounts.creator,
+                mint: &ctx.accounts.mint,
+                user_token_account: buyer_token_account,
+                token_vault: &ctx.accounts.token_vault,
+                sol_vault: &ctx.accounts.sol_vault,
+                platform_fee_recipient: &ctx.accounts.platform_fee_recipient,
+                token_program: &ctx.accounts.token_program,
+                system_program: &ctx.accounts.system_program,
+            },
+            BuyOrder {
+                sol_amount: initial_buy_sol,
+                requested_sol_amount: initial_buy_sol,
+                min_tokens_out: initial_min_tokens_out,
+            },
+        )?;
+
+        check_dev_buy_cap(&ctx.accounts.config, ctx.accounts.creator_position.tokens_bought)?;
+
+        if let Some(vesting) = &vesting {
+            let creator_vesting = ctx
+                .accounts
+                .creator_vesting
+                .as_mut()
+                .ok_or(BonkrError::MissingVestingAccounts)?;
+            creator_vesting.mint = mint_key;
+            creator_vesting.creator = ctx.accounts.creator.key();
+            creator_vesting.total_amount = tokens_bought;
+            creator_vesting.claimed_amount = 0;
+            creator_vesting.start_at = trading_starts_at;
+            creator_vesting.cliff_ends_at = trading_starts_at.saturating_add(vesting.cliff_duration);
+            creator_vesting.ends_at = trading_starts_at.saturating_add(vesting.vesting_duration);
+            creator_vesting.bump = ctx.bumps.creator_vesting.ok_or(BonkrError::MissingVestingAccounts)?;
+
+            token_state.creator_locked_tokens = tokens_bought;
+            token_state.creator_cliff_ends_at = creator_vesting.cliff_ends_at;
+            token_state.creator_vesting_ends_at = creator_vesting.ends_at;
+
+            emit!(CreatorVestingCreated {
+                mint: mint_key,
+                creator: creator_vesting.creator,
+                amount: tokens_bought,
+                cliff_ends_at: creator_vesting.cliff_ends_at,
+                ends_at: creator_vesting.ends_at,
+            });
+        }
+
+        check_graduation(token_state, &ctx.accounts.config)?;
+    }
+
+    if ctx.accounts.config.symbol_uniqueness {
+        let symbol_record = ctx
+            .accounts
+            .symbol_record
+            .as_mut()
+            .ok_or(BonkrError::MissingSymbolRecord)?;
+        // A record claimed after this launch was committed can be taken
+        // over by the reveal, so watching reveals does not let a copycat
+        // squat the ticker first.
+        if !symbol_record.symbol.is_empty() {
+            let reclaimable = !symbol_record.reserved
+                && launch_committed_slot.is_some_and(|committed_slot| symbol_record.created_slot > committed_slot);
+            require!(reclaimable, BonkrError::SymbolTaken);
+            emit!(SymbolReclaimed {
+                symbol: symbol_record.symbol.clone(),
+                previous_mint: symbol_record.mint,
+                mint: mint_key,
+            });
+        }
+        symbol_record.symbol = normalize_symbol(&token_state.symbol);
+        symbol_record.mint = mint_key;
+        symbol_record.owner = ctx.accounts.creator.key();
+        symbol_record.reserved = false;
+        symbol_record.created_at = now;
+        symbol_record.created_slot = Clock::get()?.slot;
+        symbol_record.bump = ctx.bumps.symbol_record.ok_or(BonkrError::MissingSymbolRecord)?;
+    }
+
+    if let Some(allowlist) = allowlist {
+        emit!(AllowlistSet {
+            mint: mint_key,
+            root: allowlist.root,
+            ends_at: allowlist.ends_at,
+        });
+    }
+
+    if let Some(profile) = profile {
+        let token_profile = ctx
+            .accounts
+            .token_profile
+            .as_mut()
+            .ok_or(BonkrError::MissingProfileAccount)?;
+        let bump = ctx.bumps.token_profile.ok_or(BonkrError::MissingProfileAccount)?;
+        write_token_profile(token_profile, mint_key, profile, now, bump);
+    }
+
+    emit!(TokenCreated {
+        mint: ctx.accounts.mint.key(),
+        creator: ctx.accounts.creator.key(),
+        name: token_state.name.clone(),
+        symbol: token_state.symbol.clone(),
+        index: ctx.accounts.token_index.index,
+        initial_buy_sol,
+        max_dev_buy_bp: ctx.accounts.config.max_dev_buy_bp,
+        trading_starts_at,
+        creator_prelaunch_buy,
+    });
+
+    Ok(())
+}
+
+// Grows an account written by an older program version to `space` and
+// deserializes it. Layouts are append-only, so the added bytes read back as
+// zeroed fields.
+fn load_for_migration<'info, T: AccountDeserialize>(
+    account: &AccountInfo<'info>,
+    space: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, anchor_lang::system_program::System>,
+) -> Result<T> {
+    if account.data_len() < space {
+        let top_up = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+        if top_up > 0 {
+            let cpi_context = CpiContext::new(
+                system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: account.clone(),
+                },
+            );
+            anchor_lang::system_program::transfer(cpi_context, top_up)?;
+        }
+        account.realloc(space, false)?;
+    }
+
+    let data = account.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..])
+}
+
+struct BuyAccounts<'a, 'info> {
+    user: &'a Signer<'info>,
+    mint: &'a InterfaceAccount<'info, Mint>,
+    user_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    token_vault: &'a InterfaceAccount<'info, TokenAccount>,
+    sol_vault: &'a AccountInfo<'info>,
+    platform_fee_recipient: &'a AccountInfo<'info>,
+    token_program: &'a Interface<'info, TokenInterface>,
+    system_program: &'a Program<'info, anchor_lang::system_program::System>,
+}
+
+struct BuyOrder {
+    sol_amount: u64,
+    requested_sol_amount: u64,
+    min_tokens_out: u64,
+}
+
+fn execute_buy_internal<'info>(
+    config: &GlobalConfig,
+    token_state: &mut Account<'info, TokenState>,
+    user_position: &mut Account<'info, UserPosition>,
+    accounts: BuyAccounts<'_, 'info>,
+    order: BuyOrder,
+) -> Result<u64> {
+    let BuyAccounts {
+        user,
+        mint,
+        user_token_account,
+        token_vault,
+        sol_vault,
+        platform_fee_recipient,
+        token_program,
+        system_program,
+    } = accounts;
+    let BuyOrder { sol_amount, requested_sol_amount, min_tokens_out } = order;
+    let platform_fee = (sol_amount * PLATFORM_FEE_BP) / BP_DENOMINATOR;
+    let creator_fee = (sol_amount * CREATOR_FEE_BP) / BP_DENOMINATOR;
+    let sol_to_reserve = sol_amount - platform_fee - creator_fee;
+
+    let (new_sol_reserve, new_token_reserve) = reserves_after_buy(token_state, sol_amount);
+    let tokens_out = token_state.virtual_token_reserve - new_token_reserve;
+
+    require!(tokens_out >= min_tokens_out, BonkrError::SlippageExceeded);
+    require!(tokens_out <= token_state.real_token_reserve, BonkrError::InsufficientTokens);
+    check_price_impact(config, token_state, new_sol_reserve, new_token_reserve)?;
+
+    let burn_amount = burn_fee(tokens_out);
+    let tokens_to_buyer = tokens_out - burn_amount;
+
+    let now = Clock::get()?.unix_timestamp;
+    let tokens_bought = user_position.tokens_bought + tokens_to_buyer;
+    if wallet_cap_active(config, token_state, now) {
+        let wallet_cap = (TOTAL_SUPPLY as u128 * config.max_wallet_buy_bp as u128 / BP_DENOMINATOR as u128) as u64;
+        require!(tokens_bought <= wallet_cap, BonkrError::WalletCapExceeded);
+    }
+
+    let cpi_context = CpiContext::new(
+        system_program.to_account_info(),
+        anchor_lang::system_program::Transfer {
+            from: user.to_account_info(),
+            to: sol_vault.to_account_info(),
+        },
+    );
+    anchor_lang::system_program::transfer(cpi_context, sol_to_reserve + creator_fee)?;
+
+    let cpi_context = CpiContext::new(
+        system_program.to_account_info(),
+        anchor_lang::system_program::Transfer {
+            from: user.to_account_info(),
+            to: platform_fee_recipient.to_account_info(),
+        },
+    );
+    anchor_lang::system_program::transfer(cpi_context, platform_fee)?;
+
+    let seeds = &[
+        b"token_state",
+        token_state.mint.as_ref(),
+        &[token_state.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: token_vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: user_token_account.to_account_info(),
+        authority: token_state.to_account_info(),
+    };
+    let cpi_program = token_program.to_account_info();
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
+        tokens_to_buyer,
+        mint.decimals,
+    )?;
+
+    token_state.virtual_sol_reserve = new_sol_reserve;
+    token_state.virtual_token_reserve = new_token_reserve;
+    token_state.real_sol_reserve += sol_to_reserve + creator_fee;
+    token_state.real_token_reserve -= tokens_out;
+    token_state.total_burned += burn_amount;
+    token_state.volume += sol_amount;
+    token_state.creator_fees_accrued += creator_fee;
+    record_buy(user_position, tokens_to_buyer, sol_amount, now);
+
+    emit!(TokenBought {
+        mint: token_state.mint,
+        buyer: user.key(),
+        sol_amount,
+        token_amount: tokens_to_buyer,
+        requested_sol_amount,
+        fill_bp: sol_amount * BP_DENOMINATOR / requested_sol_amount,
+    });
+
+    Ok(tokens_to_buyer)
+}
+
+fn check_deadline(deadline_unix_ts: Option<i64>, max_slot: Option<u64>) -> Result<()> {
+    let clock = Clock::get()?;
+    if let Some(deadline_unix_ts) = deadline_unix_ts {
+        require!(clock.unix_timestamp <= deadline_unix_ts, BonkrError::TransactionExpired);
+    }
+    if let Some(max_slot) = max_slot {
+        require!(clock.slot <= max_slot, BonkrError::TransactionExpired);
+    }
+    Ok(())
+}
+
+fn reserves_after_buy(token_state: &TokenState, sol_amount: u64) -> (u64, u64) {
+    let platform_fee = (sol_amount * PLATFORM_FEE_BP) / BP_DENOMINATOR;
+    let creator_fee = (sol_amount * CREATOR_FEE_BP) / BP_DENOMINATOR;
+    let sol_to_reserve = sol_amount - platform_fee - creator_fee;
+
+    let k = (token_state.virtual_sol_reserve as u128) * (token_state.virtual_token_reserve as u128);
+    let new_sol_reserve = token_state.virtual_sol_reserve + sol_to_reserve;
+    let new_token_reserve = (k / new_sol_reserve as u128) as u64;
+    (new_sol_reserve, new_token_reserve)
+}
+
+// Computed in u128: token amounts near the total supply overflow u64 once
+// multiplied by the fee basis points.
+fn burn_fee(token_amount: u64) -> u64 {
+    (token_amount as u128 * BURN_FEE_BP as u128 / BP_DENOMINATOR as u128) as u64
+}
+
+fn net_buy_sol(sol_amount: u64) -> u64 {
+    let platform_fee = (sol_amount * PLATFORM_FEE_BP) / BP_DENOMINATOR;
+    let creator_fee = (sol_amount * CREATOR_FEE_BP) / BP_DENOMINATOR;
+    sol_amount - platform_fee - creator_fee
+}
+
+// Largest buy (fees included) that neither takes more than the remaining
+// real tokens nor pushes the curve past the graduation market cap.
+fn max_fillable_sol(config: &GlobalConfig, token_state: &TokenState) -> u64 {
+    let max_buy = u64::MAX / BP_DENOMINATOR;
+    let k = (token_state.virtual_sol_reserve as u128) * (token_state.virtual_token_reserve as u128);
+
+    let min_token_reserve = token_state
+        .virtual_token_reserve
+        .saturating_sub(token_state.real_token_reserve);
+    let mut max_fill = if min_token_reserve == 0 {
+        max_buy
+    } else {
+        let max_net = (k / min_token_reserve as u128)
+            .saturating_sub(token_state.virtual_sol_reserve as u128)
+            .min(max_buy as u128) as u64;
+        let mut gross = (max_net as u128 * BP_DENOMINATOR as u128 / (BP_DENOMINATOR - PLATFORM_FEE_BP - CREATOR_FEE_BP) as u128)
+            .min(max_buy as u128) as u64;
+        while gross > 0 && net_buy_sol(gross) > max_net {
+            gross -= 1;
+        }
+        gross
+    };
+
+    // Market cap only grows with the buy size, so the smallest buy that
+    // graduates the token is found by bisection.
+    let graduates = |sol_amount: u64| {
+        let (new_sol_reserve, new_token_reserve) = reserves_after_buy(token_state, sol_amount);
+        let tokens_out = token_state.virtual_token_reserve - new_token_reserve;
+        let burn_amount = burn_fee(tokens_out);
+        market_cap_usd_at(
+            config,
+            new_sol_reserve,
+            new_token_reserve,
+            token_state.real_token_reserve.saturating_sub(tokens_out),
+            token_state.total_burned + burn_amount,
+        ) >= GRADUATION_USD as u128
+    };
+    if max_fill > 0 && graduates(max_fill) {
+        let (mut low, mut high) = (0, max_fill);
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if graduates(mid) {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+        max_fill = high;
+    }
+
+    max_fill
+}
+
+fn market_cap_usd(config: &GlobalConfig, token_state: &TokenState) -> u128 {
+    market_cap_usd_at(
+        config,
+        token_state.virtual_sol_reserve,
+        token_state.virtual_token_reserve,
+        token_state.real_token_reserve,
+        token_state.total_burned,
+    )
+}
+
+// Prices the supply selected by `market_cap_basis` at the marginal curve
+// price. Burned tokens are excluded from both bases; the circulating basis
+// also excludes tokens still held by the curve.
+fn market_cap_usd_at(
+    config: &GlobalConfig,
+    virtual_sol_reserve: u64,
+    virtual_token_reserve: u64,
+    real_token_reserve: u64,
+    total_burned: u64,
+) -> u128 {
+    let total_supply = TOTAL_SUPPLY.saturating_sub(total_burned);
+    let supply = match config.market_cap_basis {
+        MarketCapBasis::FullyDiluted => total_supply,
+        MarketCapBasis::Circulating => total_supply.saturating_sub(real_token_reserve),
+    };
+
+    let market_cap_sol = (virtual_sol_reserve as u128 * supply as u128) / virtual_token_reserve as u128;
+    (market_cap_sol * config.sol_price_usd as u128) / 1_000_000_000
+}
+
+fn curve_price(virtual_sol_reserve: u64, virtual_token_reserve: u64) -> u128 {
+    (virtual_sol_reserve as u128 * PRICE_PRECISION) / virtual_token_reserve as u128
+}
+
+fn check_price_impact(
+    config: &GlobalConfig,
+    token_state: &TokenState,
+    new_sol_reserve: u64,
+    new_token_reserve: u64,
+) -> Result<()> {
+    if config.max_price_impact_bp == 0 {
+        return Ok(());
+    }
+
+    let price_before = curve_price(token_state.virtual_sol_reserve, token_state.virtual_token_reserve);
+    let price_after = curve_price(new_sol_reserve, new_token_reserve);
+    let impact_bp = price_after.abs_diff(price_before) * BP_DENOMINATOR as u128 / price_before;
+    require!(impact_bp <= config.max_price_impact_bp as u128, BonkrError::PriceImpactExceeded);
+    Ok(())
+}
+
+// Runs once a trade has settled, so only trades backed by real SOL or tokens
+// count. The reference price is refreshed from the pre-trade price once per
+// window; a trade that moved the price past the threshold puts the token into
+// a cooldown for the trades that follow.
+fn update_circuit_breaker(config: &GlobalConfig, token_state: &mut TokenState, price_before: u128, slot: u64) {
+    if config.breaker_threshold_bp == 0 {
+        return;
+    }
+
+    if token_state.breaker_reference_price == 0
+        || slot.saturating_sub(token_state.breaker_reference_slot) > config.breaker_window_slots
+    {
+        token_state.breaker_reference_price = price_before;
+        token_state.breaker_reference_slot = slot;
+    }
+
+    let reference_price = token_state.breaker_reference_price;
+    let new_price = curve_price(token_state.virtual_sol_reserve, token_state.virtual_token_reserve);
+    let move_bp = new_price.abs_diff(reference_price) * BP_DENOMINATOR as u128 / reference_price;
+    if move_bp <= config.breaker_threshold_bp as u128 {
+        return;
+    }
+
+    token_state.breaker_cooldown_until = slot.saturating_add(config.breaker_cooldown_slots);
+    emit!(CircuitBreakerTripped {
+        mint: token_state.mint,
+        reference_price,
+        trade_price: new_price,
+        move_bp: move_bp as u64,
+        cooldown_until: token_state.breaker_cooldown_until,
+    });
+}
+
+fn validate_metadata(name: &str, symbol: &str, uri: &str) -> Result<()> {
+    require!(name.len() <= MAX_NAME_LEN, BonkrError::NameTooLong);
+    require!(uri.len() <= MAX_URI_LEN, BonkrError::UriTooLong);
+    require!(
+        !name.trim().is_empty() && !name.chars().any(char::is_control),
+        BonkrError::InvalidName
+    );
+    validate_symbol(symbol)?;
+    require!(uri.bytes().all(|b| b.is_ascii_graphic()), BonkrError::InvalidUri);
+    Ok(())
+}
+
+fn validate_symbol(symbol: &str) -> Result<()> {
+    require!(symbol.len() <= MAX_SYMBOL_LEN, BonkrError::SymbolTooLong);
+    require!(
+        !symbol.is_empty() && symbol.bytes().all(|b| b.is_ascii_alphanumeric()),
+        BonkrError::InvalidSymbol
+    );
+    Ok(())
+}
+
+fn launch_name_key(name: &str) -> [u8; 32] {
+    *blake3::hash(name.trim().to_ascii_lowercase().as_bytes()).as_bytes()
+}
+
+// A revealed launch holds its name and symbol exclusively for
+// `LAUNCH_EXCLUSIVITY_DURATION`, so copycats cannot reuse them at creation or
+// through `update_metadata`. A reveal whose commitment is older than the
+// holder's still takes precedence.
+fn claim_launch_identity(
+    claim: &mut LaunchClaim,
+    mint: Pubkey,
+    launch_committed_slot: Option<u64>,
+    now: i64,
+    bump: u8,
+) -> Result<()> {
+    if claim.mint != Pubkey::default() && claim.mint != mint && now < claim.exclusive_until {
+        require!(
+            launch_committed_slot.is_some_and(|committed_slot| committed_slot < claim.committed_slot),
+            BonkrError::LaunchIdentityClaimed
+        );
+    }
+
+    claim.bump = bump;
+    if let Some(committed_slot) = launch_committed_slot {
+        claim.mint = mint;
+        claim.committed_slot = committed_slot;
+        claim.exclusive_until = now.saturating_add(LAUNCH_EXCLUSIVITY_DURATION);
+    }
+    Ok(())
+}
+
+fn launch_commitment_hash(
+    name: &str,
+    symbol: &str,
+    uri: &str,
+    salt: &[u8; 32],
+    creator: &Pubkey,
+) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    for field in [name, symbol, uri] {
+        hasher.update(&(field.len() as u32).to_le_bytes());
+        hasher.update(field.as_bytes());
+    }
+    hasher.update(salt);
+    hasher.update(creator.as_ref());
+    *hasher.finalize().as_bytes()
+}
+
+fn pow_leading_zeros(creator: &Pubkey, mint: &Pubkey, pow: &LaunchPow) -> u32 {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(creator.as_ref());
+    hasher.update(mint.as_ref());
+    hasher.update(&pow.slot.to_le_bytes());
+    hasher.update(&pow.nonce.to_le_bytes());
+    let hash = hasher.finalize();
+
+    let mut zeros = 0;
+    for byte in hash.as_bytes() {
+        zeros += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    zeros
+}
+
+fn record_launch(
+    config: &GlobalConfig,
+    creator_profile: &mut CreatorProfile,
+    creator: Pubkey,
+    mint: Pubkey,
+    now: i64,
+    bump: u8,
+) -> Result<()> {
+    if creator_profile.creator == Pubkey::default() {
+        creator_profile.creator = creator;
+        creator_profile.bump = bump;
+    }
+
+    if creator_profile.tokens_launched > 0 && config.creator_launch_cooldown > 0 {
+        require!(
+            now >= creator_profile.last_launch_at.saturating_add(config.creator_launch_cooldown),
+            BonkrError::LaunchCooldown
+        );
+    }
+
+    if now >= creator_profile.day_started_at.saturating_add(LAUNCH_DAY) {
+        creator_profile.day_started_at = now;
+        creator_profile.launches_today = 0;
+    }
+    if config.max_launches_per_day > 0 {
+        require!(
+            creator_profile.launches_today < config.max_launches_per_day,
+            BonkrError::DailyLaunchLimit
+        );
+    }
+
+    creator_profile.tokens_launched += 1;
+    creator_profile.launches_today += 1;
+    creator_profile.last_launch_at = now;
+    if creator_profile.recent_mints.len() >= MAX_RECENT_MINTS {
+        creator_profile.recent_mints.remove(0);
+    }
+    creator_profile.recent_mints.push(mint);
+    Ok(())
+}
+
+// Case-folds the symbol and maps digits that are commonly used to imitate
+// letters, so "B0NK", "bonk" and "BONK" all claim the same record.
+fn normalize_symbol(symbol: &str) -> String {
+    symbol
+        .chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            '0' => 'O',
+            '1' | 'L' => 'I',
+            '5' => 'S',
+            '8' => 'B',
+            c => c,
+        })
+        .collect()
+}
+
+// Closes the registry record at `record` if it is held by `mint`. The account
+// may not exist, e.g. when the token launched with symbol uniqueness off.
+fn release_symbol_record<'info>(
+    record: &UncheckedAccount<'info>,
+    mint: Pubkey,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    if *record.owner != crate::ID || record.data_is_empty() {
+        return Ok(());
+    }
+
+    let symbol_record = SymbolRecord::try_deserialize(&mut &record.try_borrow_data()?[..])?;
+    if symbol_record.reserved || symbol_record.mint != mint {
+        return Ok(());
+    }
+
+    emit!(SymbolReleased {
+        symbol: symbol_record.symbol,
+        mint,
+    });
+
+    let record_info = record.to_account_info();
+    record_info.realloc(0, false)?;
+    record_info.assign(&anchor_lang::system_program::ID);
+    let lamports = record_info.lamports();
+    **record_info.try_borrow_mut_lamports()? = 0;
+    **destination.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+fn validate_token_profile(profile: &TokenProfileParams) -> Result<()> {
+    for (link, max_len) in [
+        (&profile.website, MAX_LINK_LEN),
+        (&profile.twitter, MAX_HANDLE_LEN),
+        (&profile.telegram, MAX_HANDLE_LEN),
+        (&profile.discord, MAX_HANDLE_LEN),
+    ] {
+        require!(link.len() <= max_len, BonkrError::InvalidProfile);
+        require!(link.bytes().all(|b| b.is_ascii_graphic()), BonkrError::InvalidProfile);
+    }
+    require!(profile.description.len() <= MAX_DESCRIPTION_LEN, BonkrError::InvalidProfile);
+    require!(
+        !profile.description.chars().any(|c| c.is_control() && c != '\n'),
+        BonkrError::InvalidProfile
+    );
+    Ok(())
+}
+
+fn write_token_profile(
+    token_profile: &mut TokenProfile,
+    mint: Pubkey,
+    profile: TokenProfileParams,
+    now: i64,
+    bump: u8,
+) {
+    token_profile.mint = mint;
+    token_profile.website = profile.website;
+    token_profile.twitter = profile.twitter;
+    token_profile.telegram = profile.telegram;
+    token_profile.discord = profile.discord;
+    token_profile.description = profile.description;
+    token_profile.content_hash = profile.content_hash;
+    token_profile.updated_at = now;
+    token_profile.bump = bump;
+
+    emit!(TokenProfileUpdated {
+        mint,
+        content_hash: token_profile.content_hash,
+        updated_at: now,
+    });
+}
+
+// Token-2022 mints may only carry the metadata extensions set up by
+// init_token_2022_mint; anything else (permanent delegate, transfer hooks,
+// fees) could be used against the curve vault.
+fn validate_token_2022_mint(mint: &AccountInfo, token_state: &Pubkey) -> Result<()> {
+    let mint_data = mint.try_borrow_data()?;
+    let mint_with_extensions = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    for extension_type in mint_with_extensions.get_extension_types()? {
+        require!(
+            matches!(extension_type, ExtensionType::MetadataPointer | ExtensionType::TokenMetadata),
+            BonkrError::UnsupportedMintExtension
+        );
+    }
+
+    let metadata_pointer = mint_with_extensions.get_extension::<MetadataPointer>()?;
+    require!(
+        Option::<Pubkey>::from(metadata_pointer.metadata_address) == Some(mint.key()),
+        BonkrError::UnsupportedMintExtension
+    );
+    require!(
+        Option::<Pubkey>::from(metadata_pointer.authority) == Some(*token_state),
+        BonkrError::UnsupportedMintExtension
+    );
+    Ok(())
+}
+
+fn initialize_token_metadata<'info>(
+    token_state: &Account<'info, TokenState>,
+    mint: &InterfaceAccount<'info, Mint>,
+    payer: &Signer<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    system_program: &Program<'info, anchor_lang::system_program::System>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let token_metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey::try_from(Some(token_state.key()))?,
+        mint: mint.key(),
+        name: token_state.name.clone(),
+        symbol: token_state.symbol.clone(),
+        uri: token_state.uri.clone(),
+        additional_metadata: vec![],
+    };
+    let mint_info = mint.to_account_info();
+    let new_len = mint_info.data_len() + token_metadata.tlv_size_of()?;
+    let lamports = Rent::get()?.minimum_balance(new_len).saturating_sub(mint_info.lamports());
+    if lamports > 0 {
+        let cpi_context = CpiContext::new(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: payer.to_account_info(),
+                to: mint_info.clone(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, lamports)?;
+    }
+
+    let cpi_accounts = TokenMetadataInitialize {
+        program_id: token_program.to_account_info(),
+        metadata: mint_info.clone(),
+        update_authority: token_state.to_account_info(),
+        mint_authority: token_state.to_account_info(),
+        mint: mint_info,
+    };
+    let cpi_program = token_program.to_account_info();
+    token_interface::token_metadata_initialize(
+        CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
+        token_metadata.name,
+        token_metadata.symbol,
+        token_metadata.uri,
+    )
+}
+
+// Keeps the mint-side metadata extension in line with TokenState. Mints
+// without the extension (legacy or plain Token-2022) are left untouched.
+fn sync_token_metadata<'info>(
+    token_state: &Account<'info, TokenState>,
+    mint: &InterfaceAccount<'info, Mint>,
+    payer: &Signer<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    system_program: &Program<'info, anchor_lang::system_program::System>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    let (old_size, new_size) = {
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint_with_extensions = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        let mut token_metadata = match mint_with_extensions.get_variable_len_extension::<TokenMetadata>() {
+            Ok(token_metadata) => token_metadata,
+            Err(_) => return Ok(()),
+        };
+        let old_size = token_metadata.tlv_size_of()?;
+        token_metadata.name = token_state.name.clone();
+        token_metadata.symbol = token_state.symbol.clone();
+        token_metadata.uri = token_state.uri.clone();
+        (old_size, token_metadata.tlv_size_of()?)
+    };
+
+    if new_size > old_size {
+        let new_len = mint_info.data_len() + new_size - old_size;
+        let lamports = Rent::get()?.minimum_balance(new_len).saturating_sub(mint_info.lamports());
+        if lamports > 0 {
+            let cpi_context = CpiContext::new(
+                system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: mint_info.clone(),
+                },
+            );
+            anchor_lang::system_program::transfer(cpi_context, lamports)?;
+        }
+    }
+
+    for (field, value) in [
+        (Field::Name, &token_state.name),
+        (Field::Symbol, &token_state.symbol),
+        (Field::Uri, &token_state.uri),
+    ] {
+        let cpi_accounts = TokenMetadataUpdateField {
+            program_id: token_program.to_account_info(),
+            metadata: mint_info.clone(),
+            update_authority: token_state.to_account_info(),
+        };
+        let cpi_program = token_program.to_account_info();
+        token_interface::token_metadata_update_field(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds),
+            field,
+            value.clone(),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn assert_fixed_supply(mint: &Mint) -> Result<()> {
+    require!(mint.mint_authority.is_none(), BonkrError::SupplyNotFixed);
+    require!(mint.freeze_authority.is_none(), BonkrError::SupplyNotFixed);
+    require!(mint.supply == TOTAL_SUPPLY, BonkrError::SupplyNotFixed);
+    Ok(())
+}
+
+fn vested_amount(creator_vesting: &CreatorVesting, now: i64) -> u64 {
+    if now < creator_vesting.cliff_ends_at {
+        return 0;
+    }
+    if now >= creator_vesting.ends_at {
+        return creator_vesting.total_amount;
+    }
+    let elapsed = (now - creator_vesting.start_at) as u128;
+    let duration = (creator_vesting.ends_at - creator_vesting.start_at) as u128;
+    (creator_vesting.total_amount as u128 * elapsed / duration) as u64
+}
+
+fn init_user_position(user_position: &mut UserPosition, mint: Pubkey, user: Pubkey, bump: u8) {
+    if user_position.user == Pubkey::default() {
+        user_position.mint = mint;
+        user_position.user = user;
+        user_position.tokens_bought = 0;
+        user_position.tokens_sold = 0;
+        user_position.tokens_held = 0;
+        user_position.sol_spent = 0;
+        user_position.sol_received = 0;
+        user_position.cost_basis = 0;
+        user_position.avg_cost_price = 0;
+        user_position.realized_pnl = 0;
+        user_position.first_trade_at = 0;
+        user_position.last_trade_at = 0;
+        user_position.bump = bump;
+    }
+}
+
+fn record_buy(user_position: &mut UserPosition, token_amount: u64, sol_amount: u64, now: i64) {
+    user_position.tokens_bought += token_amount;
+    user_position.tokens_held += token_amount;
+    user_position.sol_spent += sol_amount;
+    user_position.cost_basis += sol_amount;
+    refresh_user_position(user_position, now);
+}
+
+// Only tokens acquired through the curve carry a cost basis; proceeds from any
+// tokens received elsewhere are counted in sol_received but not in realized PnL.
+fn record_sell(user_position: &mut UserPosition, token_amount: u64, sol_amount: u64, now: i64) {
+    let tracked = token_amount.min(user_position.tokens_held);
+    let cost_removed = if user_position.tokens_held > 0 {
+        (user_position.cost_basis as u128 * tracked as u128 / user_position.tokens_held as u128) as u64
+    } else {
+        0
+    };
+    let tracked_proceeds = (sol_amount as u128 * tracked as u128 / token_amount as u128) as u64;
+
+    user_position.tokens_sold += token_amount;
+    user_position.tokens_held -= tracked;
+    user_position.sol_received += sol_amount;
+    user_position.cost_basis -= cost_removed;
+    user_position.realized_pnl += tracked_proceeds as i64 - cost_removed as i64;
+    refresh_user_position(user_position, now);
+}
+
+fn refresh_user_position(user_position: &mut UserPosition, now: i64) {
+    user_position.avg_cost_price = if user_position.tokens_held > 0 {
+        user_position.cost_basis as u128 * PRICE_PRECISION / user_position.tokens_held as u128
+    } else {
+        0
+    };
+    if user_position.first_trade_at == 0 {
+        user_position.first_trade_at = now;
+    }
+    user_position.last_trade_at = now;
+}
+
+fn wallet_cap_active(config: &GlobalConfig, token_state: &TokenState, now: i64) -> bool {
+    if config.max_wallet_buy_bp == 0 || token_state.is_graduated {
+        return false;
+    }
+    // Scheduled launches open trading after creation, so the window starts
+    // with the first public buy rather than at `created_at`.
+    config.wallet_cap_duration == 0
+        || now < token_state.trading_starts_at.saturating_add(config.wallet_cap_duration)
+}
+
+struct TradeGuardCheck {
+    user: Pubkey,
+    bump: u8,
+    is_buy: bool,
+    now: i64,
+    slot: u64,
+}
+
+// Rejects a trade in the opposite direction of the wallet's previous trade on
+// this token within `sandwich_guard_slots`, which stops a single wallet from
+// buying and selling around a victim in the same block.
+fn enforce_trade_guard(
+    config: &GlobalConfig,
+    token_state: &TokenState,
+    trade_guard: &mut TradeGuard,
+    check: TradeGuardCheck,
+) -> Result<()> {
+    let TradeGuardCheck { user, bump, is_buy, now, slot } = check;
+    if trade_guard.user == Pubkey::default() {
+        trade_guard.mint = token_state.mint;
+        trade_guard.user = user;
+        trade_guard.last_trade_slot = 0;
+        trade_guard.last_trade_was_buy = false;
+        trade_guard.bump = bump;
+    } else if config.sandwich_guard_slots > 0
+        && (config.sandwich_guard_duration == 0
+            || now < token_state.trading_starts_at.saturating_add(config.sandwich_guard_duration))
+        && trade_guard.last_trade_was_buy != is_buy
+    {
+        require!(
+            slot >= trade_guard.last_trade_slot.saturating_add(config.sandwich_guard_slots),
+            BonkrError::SandwichGuard
+        );
+    }
+
+    trade_guard.last_trade_slot = slot;
+    trade_guard.last_trade_was_buy = is_buy;
+    Ok(())
+}
+
+fn check_dev_buy_cap(config: &GlobalConfig, creator_tokens_bought: u64) -> Result<()> {
+    if config.max_dev_buy_bp > 0 {
+        let max_dev_buy = (TOTAL_SUPPLY as u128 * config.max_dev_buy_bp as u128 / BP_DENOMINATOR as u128) as u64;
+        require!(creator_tokens_bought <= max_dev_buy, BonkrError::DevBuyTooLarge);
+    }
+    Ok(())
+}
+
+fn validate_allowlist_phase(trading_starts_at: i64, ends_at: i64) -> Result<()> {
+    require!(
+        ends_at > trading_starts_at && ends_at <= trading_starts_at.saturating_add(MAX_ALLOWLIST_DURATION),
+        BonkrError::InvalidAllowlistPhase
+    );
+    Ok(())
+}
+
+fn allowlist_active(token_state: &TokenState, now: i64) -> bool {
+    token_state.allowlist_root != [0; 32] && now < token_state.allowlist_ends_at
+}
+
+fn allowlist_leaf(user: &Pubkey, max_tokens: u64) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[0]);
+    hasher.update(user.as_ref());
+    hasher.update(&max_tokens.to_le_bytes());
+    *hasher.finalize().as_bytes()
+}
+
+fn verify_allowlist_proof(root: &[u8; 32], user: &Pubkey, allowlist_proof: &AllowlistProof) -> bool {
+    if allowlist_proof.proof.len() > MAX_ALLOWLIST_PROOF_LEN {
+        return false;
+    }
+
+    let mut node = allowlist_leaf(user, allowlist_proof.max_tokens);
+    for sibling in allowlist_proof.proof.iter() {
+        let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&[1]);
+        hasher.update(&left);
+        hasher.update(&right);
+        node = *hasher.finalize().as_bytes();
+    }
+    node == *root
+}
+
+// Accounts that predate `migrate_config`/`migrate_token_state` still hold the
+// old `is_paused` bool in `pause_flags`, where `true` would read as
+// `PAUSE_BUY` alone. Treat it as the full pause it used to be.
+fn factory_pause_flags(config: &GlobalConfig, now: i64) -> u8 {
+    let pause_flags = if config.version < CONFIG_VERSION && config.pause_flags != 0 {
+        PAUSE_ALL
+    } else {
+        config.pause_flags
+    };
+    if now < config.guardian_paused_until {
+        pause_flags | config.guardian_pause_flags
+    } else {
+        pause_flags
+    }
+}
+
+fn token_pause_flags(token_state: &TokenState, now: i64) -> u8 {
+    let pause_flags = if token_state.version < TOKEN_STATE_VERSION && token_state.pause_flags != 0 {
+        PAUSE_TRADING
+    } else {
+        token_state.pause_flags
+    };
+    if now < token_state.guardian_paused_until {
+        pause_flags | token_state.guardian_pause_flags
+    } else {
+        pause_flags
+    }
+}
+
+fn check_graduation(token_state: &mut Account<TokenState>, config: &GlobalConfig) -> Result<()> {
+    if token_state.is_graduated {
+        return Ok(());
+    }
+
+    let market_cap_usd = market_cap_usd(config, token_state);
+
+    if market_cap_usd >= GRADUATION_USD as u128 {
+        token_state.is_graduated = true;
+        emit!(TokenGraduated {
+            mint: token_state.mint,
+            market_cap_usd: market_cap_usd as u64,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + GlobalConfig::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: This is the platform fee recipient account
+    pub platform_fee_recipient: AccountInfo<'info>,
+    pub system_program: Program<'info, anchor_lang::system_program::System>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: LaunchParams)]
+pub struct CreateToken<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
         mint::decimals = 9,
         mint::authority = token_state,
+        mint::token_program = token_program,
     )]
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         init,
@@ -494,114 +2464,521 @@ pub struct CreateToken<'info> {
     #[account(
         init,
         payer = creator,
+        space = 8 + TokenIndexEntry::INIT_SPACE,
+        seeds = [b"token_index", config.token_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub token_index: Account<'info, TokenIndexEntry>,
+    
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = mint,
+        associated_token::authority = token_state,
+        associated_token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"sol_vault", mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: This is a PDA used as a SOL vault
+    pub sol_vault: AccountInfo<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_program,
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + UserPosition::INIT_SPACE,
+        seeds = [b"user_position", mint.key().as_ref(), creator.key().as_ref()],
+        bump
+    )]
+    pub creator_position: Account<'info, UserPosition>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + CreatorVesting::INIT_SPACE,
+        seeds = [b"creator_vesting", mint.key().as_ref()],
+        bump
+    )]
+    pub creator_vesting: Option<Box<Account<'info, CreatorVesting>>>,
+    
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = mint,
+        associated_token::authority = creator_vesting,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + TokenProfile::INIT_SPACE,
+        seeds = [b"token_profile", mint.key().as_ref()],
+        bump
+    )]
+    pub token_profile: Option<Box<Account<'info, TokenProfile>>>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + SymbolRecord::INIT_SPACE,
+        seeds = [b"symbol", normalize_symbol(&params.symbol).as_bytes()],
+        bump
+    )]
+    pub symbol_record: Option<Box<Account<'info, SymbolRecord>>>,
+    
+    #[account(
+        mut,
+        close = creator,
+        has_one = creator @ BonkrError::NotCreator,
+        seeds = [b"launch_commitment", creator.key().as_ref(), launch_commitment.commitment.as_ref()],
+        bump = launch_commitment.bump
+    )]
+    pub launch_commitment: Option<Box<Account<'info, LaunchCommitment>>>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + LaunchClaim::INIT_SPACE,
+        seeds = [b"name_claim", launch_name_key(&params.name).as_ref()],
+        bump
+    )]
+    pub name_claim: Box<Account<'info, LaunchClaim>>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + LaunchClaim::INIT_SPACE,
+        seeds = [b"symbol_claim", normalize_symbol(&params.symbol).as_bytes()],
+        bump
+    )]
+    pub symbol_claim: Box<Account<'info, LaunchClaim>>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", creator.key().as_ref()],
+        bump
+    )]
+    pub creator_profile: Box<Account<'info, CreatorProfile>>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        address = config.platform_fee_recipient @ BonkrError::InvalidFeeRecipient
+    )]
+    /// CHECK: Validated by config.platform_fee_recipient
+    pub platform_fee_recipient: AccountInfo<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, anchor_lang::system_program::System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct InitToken2022Mint<'info> {
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = 9,
+        mint::authority = token_state,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = token_state,
+        extensions::metadata_pointer::metadata_address = mint,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump
+    )]
+    /// CHECK: Token state PDA, initialized by create_token
+    pub token_state: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, anchor_lang::system_program::System>,
+}
+
+#[derive(Accounts)]
+pub struct Trade<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump
+    )]
+    pub token_state: Account<'info, TokenState>,
+    
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = token_state,
+        associated_token::token_program = token_program,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"sol_vault", mint.key().as_ref()],
+        bump = token_state.vault_bump
+    )]
+    /// CHECK: This is a PDA used as a SOL vault
+    pub sol_vault: AccountInfo<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserPosition::INIT_SPACE,
+        seeds = [b"user_position", mint.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + TradeGuard::INIT_SPACE,
+        seeds = [b"trade_guard", mint.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub trade_guard: Account<'info, TradeGuard>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        address = config.platform_fee_recipient @ BonkrError::InvalidFeeRecipient
+    )]
+    /// CHECK: Validated by config.platform_fee_recipient
+    pub platform_fee_recipient: AccountInfo<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, anchor_lang::system_program::System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump
+    )]
+    pub token_state: Account<'info, TokenState>,
+    
+    #[account(
+        mut,
+        seeds = [b"creator_vesting", mint.key().as_ref()],
+        bump = creator_vesting.bump,
+        has_one = creator @ BonkrError::NotCreator
+    )]
+    pub creator_vesting: Account<'info, CreatorVesting>,
+    
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = creator_vesting,
+        associated_token::token_program = token_program,
+    )]
+    pub vesting_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_program,
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, anchor_lang::system_program::System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", token_state.mint.as_ref()],
+        bump = token_state.bump
+    )]
+    pub token_state: Account<'info, TokenState>,
+    
+    #[account(
+        mut,
+        seeds = [b"sol_vault", token_state.mint.as_ref()],
+        bump = token_state.vault_bump
+    )]
+    /// CHECK: This is a PDA used as a SOL vault
+    pub sol_vault: AccountInfo<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", creator.key().as_ref()],
+        bump
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub system_program: Program<'info, anchor_lang::system_program::System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteWithdrawLP<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"pending_action", pending_action.id.to_le_bytes().as_ref()],
+        bump = pending_action.bump
+    )]
+    pub pending_action: Account<'info, PendingAction>,
+    
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump
+    )]
+    pub token_state: Account<'info, TokenState>,
+    
+    #[account(
+        mut,
         associated_token::mint = mint,
         associated_token::authority = token_state,
+        associated_token::token_program = token_program,
     )]
-    pub token_vault: Account<'info, TokenAccount>,
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         mut,
         seeds = [b"sol_vault", mint.key().as_ref()],
-        bump
+        bump = token_state.vault_bump
     )]
     /// CHECK: This is a PDA used as a SOL vault
     pub sol_vault: AccountInfo<'info>,
     
     #[account(
         init_if_needed,
-        payer = creator,
+        payer = executor,
         associated_token::mint = mint,
-        associated_token::authority = creator,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program,
     )]
-    pub creator_token_account: Account<'info, TokenAccount>,
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
     
     #[account(mut)]
-    pub creator: Signer<'info>,
+    /// CHECK: Validated against the scheduled action
+    pub recipient: AccountInfo<'info>,
     
     #[account(mut)]
-    /// CHECK: Validated by config.platform_fee_recipient
-    pub platform_fee_recipient: AccountInfo<'info>,
+    /// CHECK: Validated by config.authority, receives the pending action rent
+    pub authority: AccountInfo<'info>,
+    
+    #[account(mut)]
+    pub executor: Signer<'info>,
     
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, anchor_lang::system_program::System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct Trade<'info> {
+#[instruction(name: String, symbol: String)]
+pub struct UpdateMetadata<'info> {
     #[account(seeds = [b"config"], bump = config.bump)]
     pub config: Account<'info, GlobalConfig>,
     
-    pub mint: Account<'info, Mint>,
+    #[account(mut, mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
     
     #[account(
         mut,
         seeds = [b"token_state", mint.key().as_ref()],
-        bump = token_state.bump
+        bump = token_state.bump,
+        has_one = creator @ BonkrError::NotCreator
     )]
     pub token_state: Account<'info, TokenState>,
     
     #[account(
         mut,
-        associated_token::mint = mint,
-        associated_token::authority = token_state,
+        seeds = [b"symbol", normalize_symbol(&token_state.symbol).as_bytes()],
+        bump
     )]
-    pub token_vault: Account<'info, TokenAccount>,
+    /// CHECK: Registry record for the current symbol; released by the handler if held by this mint
+    pub old_symbol_record: UncheckedAccount<'info>,
     
     #[account(
-        mut,
-        seeds = [b"sol_vault", mint.key().as_ref()],
-        bump = token_state.vault_bump
+        init_if_needed,
+        payer = creator,
+        space = 8 + SymbolRecord::INIT_SPACE,
+        seeds = [b"symbol", normalize_symbol(&symbol).as_bytes()],
+        bump
     )]
-    /// CHECK: This is a PDA used as a SOL vault
-    pub sol_vault: AccountInfo<'info>,
+    pub symbol_record: Option<Box<Account<'info, SymbolRecord>>>,
     
     #[account(
         init_if_needed,
-        payer = user,
-        associated_token::mint = mint,
-        associated_token::authority = user,
+        payer = creator,
+        space = 8 + LaunchClaim::INIT_SPACE,
+        seeds = [b"name_claim", launch_name_key(&name).as_ref()],
+        bump
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub name_claim: Box<Account<'info, LaunchClaim>>,
     
-    #[account(mut)]
-    pub user: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + LaunchClaim::INIT_SPACE,
+        seeds = [b"symbol_claim", normalize_symbol(&symbol).as_bytes()],
+        bump
+    )]
+    pub symbol_claim: Box<Account<'info, LaunchClaim>>,
     
     #[account(mut)]
-    /// CHECK: Validated by config.platform_fee_recipient
-    pub platform_fee_recipient: AccountInfo<'info>,
+    pub creator: Signer<'info>,
     
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, anchor_lang::system_program::System>,
 }
 
 #[derive(Accounts)]
-pub struct ClaimCreatorFees<'info> {
+pub struct SetTokenProfile<'info> {
     #[account(
-        mut,
         seeds = [b"token_state", token_state.mint.as_ref()],
-        bump = token_state.bump
+        bump = token_state.bump,
+        has_one = creator @ BonkrError::NotCreator
     )]
     pub token_state: Account<'info, TokenState>,
     
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + TokenProfile::INIT_SPACE,
+        seeds = [b"token_profile", token_state.mint.as_ref()],
+        bump
+    )]
+    pub token_profile: Account<'info, TokenProfile>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub system_program: Program<'info, anchor_lang::system_program::System>,
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32])]
+pub struct CommitLaunch<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + LaunchCommitment::INIT_SPACE,
+        seeds = [b"launch_commitment", creator.key().as_ref(), commitment.as_ref()],
+        bump
+    )]
+    pub launch_commitment: Account<'info, LaunchCommitment>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    pub system_program: Program<'info, anchor_lang::system_program::System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelLaunchCommitment<'info> {
     #[account(
         mut,
-        seeds = [b"sol_vault", token_state.mint.as_ref()],
-        bump = token_state.vault_bump
+        close = creator,
+        has_one = creator @ BonkrError::NotCreator,
+        seeds = [b"launch_commitment", creator.key().as_ref(), launch_commitment.commitment.as_ref()],
+        bump = launch_commitment.bump
     )]
-    /// CHECK: This is a PDA used as a SOL vault
-    pub sol_vault: AccountInfo<'info>,
+    pub launch_commitment: Account<'info, LaunchCommitment>,
     
     #[account(mut)]
     pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(symbol: String)]
+pub struct ReserveSymbol<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SymbolRecord::INIT_SPACE,
+        seeds = [b"symbol", normalize_symbol(&symbol).as_bytes()],
+        bump
+    )]
+    pub symbol_record: Account<'info, SymbolRecord>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
     
     pub system_program: Program<'info, anchor_lang::system_program::System>,
 }
 
 #[derive(Accounts)]
-pub struct AdminWithdrawLP<'info> {
+pub struct ReleaseSymbol<'info> {
     #[account(
         seeds = [b"config"],
         bump = config.bump,
@@ -609,52 +2986,88 @@ pub struct AdminWithdrawLP<'info> {
     )]
     pub config: Account<'info, GlobalConfig>,
     
-    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"symbol", symbol_record.symbol.as_bytes()],
+        bump = symbol_record.bump
+    )]
+    pub symbol_record: Account<'info, SymbolRecord>,
     
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreatorAction<'info> {
     #[account(
         mut,
-        seeds = [b"token_state", mint.key().as_ref()],
-        bump = token_state.bump
+        seeds = [b"token_state", token_state.mint.as_ref()],
+        bump = token_state.bump,
+        has_one = creator @ BonkrError::NotCreator
     )]
     pub token_state: Account<'info, TokenState>,
     
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdminAction<'info> {
     #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = token_state,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority
     )]
-    pub token_vault: Account<'info, TokenAccount>,
+    pub config: Account<'info, GlobalConfig>,
     
     #[account(
         mut,
-        seeds = [b"sol_vault", mint.key().as_ref()],
-        bump = token_state.vault_bump
+        seeds = [b"token_state", token_state.mint.as_ref()],
+        bump = token_state.bump
     )]
-    /// CHECK: This is a PDA used as a SOL vault
-    pub sol_vault: AccountInfo<'info>,
+    pub token_state: Account<'info, TokenState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CrankGraduation<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
     
     #[account(
-        init_if_needed,
-        payer = authority,
-        associated_token::mint = mint,
-        associated_token::authority = recipient,
+        mut,
+        seeds = [b"token_state", token_state.mint.as_ref()],
+        bump = token_state.bump
     )]
-    pub recipient_token_account: Account<'info, TokenAccount>,
+    pub token_state: Account<'info, TokenState>,
+    
+    #[account(mut, seeds = [b"bounty_pool"], bump = bounty_pool.bump)]
+    pub bounty_pool: Option<Account<'info, BountyPool>>,
     
     #[account(mut)]
-    /// CHECK: Recipient can be any account
-    pub recipient: AccountInfo<'info>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundBountyPool<'info> {
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = 8 + BountyPool::INIT_SPACE,
+        seeds = [b"bounty_pool"],
+        bump
+    )]
+    pub bounty_pool: Account<'info, BountyPool>,
     
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub funder: Signer<'info>,
     
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, anchor_lang::system_program::System>,
 }
 
 #[derive(Accounts)]
-pub struct AdminAction<'info> {
+pub struct WithdrawBountyPool<'info> {
     #[account(
         seeds = [b"config"],
         bump = config.bump,
@@ -662,14 +3075,49 @@ pub struct AdminAction<'info> {
     )]
     pub config: Account<'info, GlobalConfig>,
     
+    #[account(mut, seeds = [b"bounty_pool"], bump = bounty_pool.bump)]
+    pub bounty_pool: Account<'info, BountyPool>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ViewMarketCap<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+    
     #[account(
-        mut,
         seeds = [b"token_state", token_state.mint.as_ref()],
         bump = token_state.bump
     )]
     pub token_state: Account<'info, TokenState>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(mut, seeds = [b"config"], bump, owner = crate::ID)]
+    /// CHECK: Deserialized by the handler after it is reallocated
+    pub config: UncheckedAccount<'info>,
     
+    #[account(mut)]
     pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, anchor_lang::system_program::System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateTokenState<'info> {
+    pub mint: InterfaceAccount<'info, Mint>,
+    
+    #[account(mut, seeds = [b"token_state", mint.key().as_ref()], bump, owner = crate::ID)]
+    /// CHECK: Deserialized by the handler after it is reallocated
+    pub token_state: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, anchor_lang::system_program::System>,
 }
 
 #[derive(Accounts)]
@@ -682,11 +3130,118 @@ pub struct UpdateConfig<'info> {
     )]
     pub config: Account<'info, GlobalConfig>,
     
-    pub authority: Signer<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ScheduleAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PendingAction::INIT_SPACE,
+        seeds = [b"pending_action", config.action_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pending_action: Account<'info, PendingAction>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, anchor_lang::system_program::System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAction<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"pending_action", pending_action.id.to_le_bytes().as_ref()],
+        bump = pending_action.bump
+    )]
+    pub pending_action: Account<'info, PendingAction>,
+    
+    #[account(mut)]
+    /// CHECK: Validated by config.authority, receives the pending action rent
+    pub authority: AccountInfo<'info>,
+    
+    #[account(
+        constraint = guardian.key() == config.guardian || guardian.key() == config.authority @ BonkrError::Unauthorized
+    )]
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteConfigAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"pending_action", pending_action.id.to_le_bytes().as_ref()],
+        bump = pending_action.bump
+    )]
+    pub pending_action: Account<'info, PendingAction>,
+    
+    #[account(mut)]
+    /// CHECK: Validated by config.authority, receives the pending action rent
+    pub authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GuardianConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = guardian
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GuardianAction<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = guardian
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_state", token_state.mint.as_ref()],
+        bump = token_state.bump
+    )]
+    pub token_state: Account<'info, TokenState>,
+    
+    pub guardian: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct EmergencyWithdraw<'info> {
+pub struct ExecuteEmergencyWithdraw<'info> {
     #[account(
         seeds = [b"config"],
         bump = config.bump,
@@ -694,14 +3249,38 @@ pub struct EmergencyWithdraw<'info> {
     )]
     pub config: Account<'info, GlobalConfig>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"pending_action", pending_action.id.to_le_bytes().as_ref()],
+        bump = pending_action.bump
+    )]
+    pub pending_action: Account<'info, PendingAction>,
+    
+    #[account(
+        mut,
+        seeds = [b"token_state", token_state.mint.as_ref()],
+        bump = token_state.bump
+    )]
+    pub token_state: Account<'info, TokenState>,
+    
+    #[account(
+        mut,
+        seeds = [b"sol_vault", token_state.mint.as_ref()],
+        bump = token_state.vault_bump
+    )]
     /// CHECK: This is a PDA used as a SOL vault
     pub sol_vault: AccountInfo<'info>,
     
     #[account(mut)]
-    pub authority: Signer<'info>,
+    /// CHECK: Validated against the scheduled action
+    pub recipient: AccountInfo<'info>,
     
-    pub system_program: Program<'info, anchor_lang::system_program::System>,
+    #[account(mut)]
+    /// CHECK: Validated by config.authority, receives the pending action rent
+    pub authority: AccountInfo<'info>,
+    
+    pub executor: Signer<'info>,
 }
 
 #[account]
@@ -713,8 +3292,59 @@ pub struct GlobalConfig {
     pub creation_fee: u64,
     pub token_count: u64,
     pub sol_price_usd: u64,
-    pub is_paused: bool,
+    pub pause_flags: u8,
     pub bump: u8,
+    // Fields below were added after the first deployment. Only append new
+    // fields so `migrate_config` can grow live accounts in place.
+    pub guardian: Pubkey,
+    pub timelock_delay: i64,
+    pub action_count: u64,
+    pub guardian_pause_duration: i64,
+    pub guardian_pause_flags: u8,
+    pub guardian_paused_until: i64,
+    pub breaker_threshold_bp: u64,
+    pub breaker_window_slots: u64,
+    pub breaker_cooldown_slots: u64,
+    pub max_wallet_buy_bp: u64,
+    pub wallet_cap_duration: i64,
+    pub max_dev_buy_bp: u64,
+    pub symbol_uniqueness: bool,
+    pub creator_launch_cooldown: i64,
+    pub max_launches_per_day: u32,
+    pub pow_difficulty: u8,
+    pub sandwich_guard_slots: u64,
+    pub sandwich_guard_duration: i64,
+    pub max_price_impact_bp: u64,
+    pub graduation_bounty: u64,
+    pub market_cap_basis: MarketCapBasis,
+    pub guardian_pause_cooldown_until: i64,
+    pub version: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum MarketCapBasis {
+    FullyDiluted,
+    Circulating,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct PendingAction {
+    pub id: u64,
+    pub action: PendingActionKind,
+    pub scheduled_at: i64,
+    pub execute_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub enum PendingActionKind {
+    SetPlatformFeeRecipient { recipient: Pubkey },
+    SetCreationFee { fee: u64 },
+    SetGuardian { guardian: Pubkey },
+    SetTimelockDelay { delay: i64 },
+    WithdrawLp { mint: Pubkey, recipient: Pubkey },
+    EmergencyWithdraw { mint: Pubkey, recipient: Pubkey },
 }
 
 #[account]
@@ -736,10 +3366,200 @@ pub struct TokenState {
     pub volume: u64,
     pub creator_fees_accrued: u64,
     pub is_graduated: bool,
-    pub is_paused: bool,
+    pub pause_flags: u8,
     pub created_at: i64,
     pub bump: u8,
     pub vault_bump: u8,
+    // Fields below were added after the first deployment. Only append new
+    // fields so `migrate_token_state` can grow live accounts in place.
+    pub guardian_pause_flags: u8,
+    pub guardian_paused_until: i64,
+    pub breaker_reference_price: u128,
+    pub breaker_reference_slot: u64,
+    pub breaker_cooldown_until: u64,
+    pub trading_starts_at: i64,
+    pub creator_prelaunch_buy: bool,
+    pub allowlist_root: [u8; 32],
+    pub allowlist_ends_at: i64,
+    pub creator_locked_tokens: u64,
+    pub creator_cliff_ends_at: i64,
+    pub creator_vesting_ends_at: i64,
+    pub metadata_locked: bool,
+    pub guardian_pause_cooldown_until: i64,
+    pub version: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorProfile {
+    pub creator: Pubkey,
+    pub tokens_launched: u64,
+    #[max_len(10)]
+    pub recent_mints: Vec<Pubkey>,
+    pub total_creator_fees: u64,
+    pub last_launch_at: i64,
+    pub day_started_at: i64,
+    pub launches_today: u32,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TokenIndexEntry {
+    pub index: u64,
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct SymbolRecord {
+    #[max_len(10)]
+    pub symbol: String,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub reserved: bool,
+    pub created_at: i64,
+    pub created_slot: u64,
+    pub bump: u8,
+}
+
+// Platform-funded lamports that pay graduation crank bounties.
+#[account]
+#[derive(InitSpace)]
+pub struct BountyPool {
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchClaim {
+    pub mint: Pubkey,
+    pub committed_slot: u64,
+    pub exclusive_until: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LaunchCommitment {
+    pub creator: Pubkey,
+    pub commitment: [u8; 32],
+    pub committed_slot: u64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TokenProfile {
+    pub mint: Pubkey,
+    #[max_len(100)]
+    pub website: String,
+    #[max_len(64)]
+    pub twitter: String,
+    #[max_len(64)]
+    pub telegram: String,
+    #[max_len(64)]
+    pub discord: String,
+    #[max_len(280)]
+    pub description: String,
+    pub content_hash: [u8; 32],
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LaunchParams {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub initial_buy_sol: u64,
+    pub initial_min_tokens_out: u64,
+    pub trading_starts_at: Option<i64>,
+    pub creator_prelaunch_buy: bool,
+    pub vesting: Option<VestingParams>,
+    pub profile: Option<TokenProfileParams>,
+    pub pow: Option<LaunchPow>,
+    pub allowlist: Option<AllowlistParams>,
+    pub deadline_unix_ts: Option<i64>,
+    pub max_slot: Option<u64>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AllowlistParams {
+    pub root: [u8; 32],
+    pub ends_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LaunchPow {
+    pub slot: u64,
+    pub nonce: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TokenProfileParams {
+    pub website: String,
+    pub twitter: String,
+    pub telegram: String,
+    pub discord: String,
+    pub description: String,
+    pub content_hash: [u8; 32],
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct CreatorVesting {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_at: i64,
+    pub cliff_ends_at: i64,
+    pub ends_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct VestingParams {
+    pub cliff_duration: i64,
+    pub vesting_duration: i64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserPosition {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub tokens_bought: u64,
+    pub tokens_sold: u64,
+    pub tokens_held: u64,
+    pub sol_spent: u64,
+    pub sol_received: u64,
+    pub cost_basis: u64,
+    pub avg_cost_price: u128,
+    pub realized_pnl: i64,
+    pub first_trade_at: i64,
+    pub last_trade_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TradeGuard {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub last_trade_slot: u64,
+    pub last_trade_was_buy: bool,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AllowlistProof {
+    pub max_tokens: u64,
+    pub proof: Vec<[u8; 32]>,
 }
 
 #[event]
@@ -748,7 +3568,11 @@ pub struct TokenCreated {
     pub creator: Pubkey,
     pub name: String,
     pub symbol: String,
+    pub index: u64,
     pub initial_buy_sol: u64,
+    pub max_dev_buy_bp: u64,
+    pub trading_starts_at: i64,
+    pub creator_prelaunch_buy: bool,
 }
 
 #[event]
@@ -757,6 +3581,8 @@ pub struct TokenBought {
     pub buyer: Pubkey,
     pub sol_amount: u64,
     pub token_amount: u64,
+    pub requested_sol_amount: u64,
+    pub fill_bp: u64,
 }
 
 #[event]
@@ -786,8 +3612,118 @@ pub recipient: Pubkey,
 }
 #[event]
 pub struct EmergencyWithdrawEvent {
+pub mint: Pubkey,
+pub amount: u64,
+pub recipient: Pubkey,
+}
+#[event]
+pub struct PauseStateChanged {
+pub mint: Option<Pubkey>,
+pub pause_flags: u8,
+pub guardian_pause_flags: u8,
+pub guardian_paused_until: i64,
+pub reason: u8,
+pub changed_by: Pubkey,
+}
+#[event]
+pub struct CreatorVestingCreated {
+pub mint: Pubkey,
+pub creator: Pubkey,
+pub amount: u64,
+pub cliff_ends_at: i64,
+pub ends_at: i64,
+}
+#[event]
+pub struct VestedClaimed {
+pub mint: Pubkey,
+pub creator: Pubkey,
+pub amount: u64,
+pub remaining_locked: u64,
+}
+#[event]
+pub struct MetadataUpdated {
+pub mint: Pubkey,
+pub name: String,
+pub symbol: String,
+pub uri: String,
+}
+#[event]
+pub struct MetadataLocked {
+pub mint: Pubkey,
+}
+#[event]
+pub struct TokenProfileUpdated {
+pub mint: Pubkey,
+pub content_hash: [u8; 32],
+pub updated_at: i64,
+}
+#[event]
+pub struct BountyPoolFunded {
+pub funder: Pubkey,
+pub amount: u64,
+}
+#[event]
+pub struct BountyPoolWithdrawn {
+pub authority: Pubkey,
 pub amount: u64,
 }
+#[event]
+pub struct GraduationCranked {
+pub mint: Pubkey,
+pub caller: Pubkey,
+pub bounty: u64,
+}
+#[event]
+pub struct LaunchCommitted {
+pub creator: Pubkey,
+pub commitment: [u8; 32],
+pub committed_slot: u64,
+}
+#[event]
+pub struct SymbolReclaimed {
+pub symbol: String,
+pub previous_mint: Pubkey,
+pub mint: Pubkey,
+}
+#[event]
+pub struct SymbolReserved {
+pub symbol: String,
+}
+#[event]
+pub struct SymbolReleased {
+pub symbol: String,
+pub mint: Pubkey,
+}
+#[event]
+pub struct AllowlistSet {
+pub mint: Pubkey,
+pub root: [u8; 32],
+pub ends_at: i64,
+}
+#[event]
+pub struct CircuitBreakerTripped {
+pub mint: Pubkey,
+pub reference_price: u128,
+pub trade_price: u128,
+pub move_bp: u64,
+pub cooldown_until: u64,
+}
+#[event]
+pub struct ActionScheduled {
+pub id: u64,
+pub action: PendingActionKind,
+pub execute_at: i64,
+}
+#[event]
+pub struct ActionCancelled {
+pub id: u64,
+pub cancelled_by: Pubkey,
+}
+#[event]
+pub struct ActionExecuted {
+pub id: u64,
+pub action: PendingActionKind,
+}
 #[error_code]
 pub enum BonkrError {
 #[msg("Factory is paused")]
@@ -816,4 +3752,527 @@ NameTooLong,
 SymbolTooLong,
 #[msg("Not the token creator")]
 NotCreator,
+#[msg("Unauthorized")]
+Unauthorized,
+#[msg("Execution time is before the timelock delay")]
+TimelockTooShort,
+#[msg("Timelock has not elapsed")]
+TimelockNotElapsed,
+#[msg("Timelock has already elapsed")]
+TimelockElapsed,
+#[msg("Invalid pending action")]
+InvalidPendingAction,
+#[msg("No active guardian pause to confirm")]
+NoGuardianPause,
+#[msg("Invalid pause flags")]
+InvalidPauseFlags,
+#[msg("Circuit breaker cooldown active")]
+CircuitBreakerCooldown,
+#[msg("Trading start time is in the past")]
+InvalidTradingStart,
+#[msg("Trading has not started")]
+TradingNotStarted,
+#[msg("Trading has already started")]
+TradingAlreadyStarted,
+#[msg("Invalid allowlist phase")]
+InvalidAllowlistPhase,
+#[msg("Buyer is not on the allowlist")]
+NotAllowlisted,
+#[msg("Allowlist cap exceeded")]
+AllowlistCapExceeded,
+#[msg("Per-wallet buy cap exceeded")]
+WalletCapExceeded,
+#[msg("Invalid vesting parameters")]
+InvalidVesting,
+#[msg("Vesting accounts are required")]
+MissingVestingAccounts,
+#[msg("Nothing vested to claim")]
+NothingVested,
+#[msg("Initial buy exceeds the dev buy limit")]
+DevBuyTooLarge,
+#[msg("Mint supply is not fixed")]
+SupplyNotFixed,
+#[msg("Unsupported mint extension")]
+UnsupportedMintExtension,
+#[msg("URI too long (max 200 chars)")]
+UriTooLong,
+#[msg("Invalid name")]
+InvalidName,
+#[msg("Invalid symbol")]
+InvalidSymbol,
+#[msg("Invalid URI")]
+InvalidUri,
+#[msg("Metadata is locked")]
+MetadataLocked,
+#[msg("Invalid token profile")]
+InvalidProfile,
+#[msg("Token profile account is required")]
+MissingProfileAccount,
+#[msg("Symbol record is required when symbol uniqueness is enabled")]
+MissingSymbolRecord,
+#[msg("Creator launch cooldown active")]
+LaunchCooldown,
+#[msg("Daily launch limit reached")]
+DailyLaunchLimit,
+#[msg("Invalid proof of work")]
+InvalidProofOfWork,
+#[msg("Proof of work slot is too old")]
+StaleProofOfWork,
+#[msg("Symbol is already taken")]
+SymbolTaken,
+#[msg("Invalid launch commitment")]
+InvalidLaunchCommitment,
+#[msg("Reveal window is closed")]
+RevealWindowClosed,
+#[msg("Transaction expired")]
+TransactionExpired,
+#[msg("Opposite-side trade in the same slot window is not allowed")]
+SandwichGuard,
+#[msg("Price impact exceeds maximum")]
+PriceImpactExceeded,
+#[msg("Market cap is below the graduation threshold")]
+GraduationThresholdNotMet,
+#[msg("Account is already migrated")]
+AlreadyMigrated,
+#[msg("Guardian pause is active or cooling down")]
+GuardianPauseCooldown,
+#[msg("Name or symbol is reserved by a recent launch")]
+LaunchIdentityClaimed,
+#[msg("Platform fee recipient does not match the config")]
+InvalidFeeRecipient,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config(market_cap_basis: MarketCapBasis, sol_price_usd: u64) -> GlobalConfig {
+        GlobalConfig {
+            authority: Pubkey::default(),
+            platform_fee_recipient: Pubkey::default(),
+            oracle: Pubkey::default(),
+            creation_fee: 0,
+            token_count: 0,
+            sol_price_usd,
+            pause_flags: 0,
+            bump: 0,
+            guardian: Pubkey::default(),
+            timelock_delay: 0,
+            action_count: 0,
+            guardian_pause_duration: DEFAULT_GUARDIAN_PAUSE_DURATION,
+            guardian_pause_flags: 0,
+            guardian_paused_until: 0,
+            breaker_threshold_bp: 0,
+            breaker_window_slots: 0,
+            breaker_cooldown_slots: 0,
+            max_wallet_buy_bp: 0,
+            wallet_cap_duration: 0,
+            max_dev_buy_bp: 0,
+            symbol_uniqueness: false,
+            creator_launch_cooldown: 0,
+            max_launches_per_day: 0,
+            pow_difficulty: 0,
+            sandwich_guard_slots: 0,
+            sandwich_guard_duration: 0,
+            max_price_impact_bp: 0,
+            graduation_bounty: 0,
+            market_cap_basis,
+            guardian_pause_cooldown_until: 0,
+            version: CONFIG_VERSION,
+        }
+    }
+
+    fn new_token_state() -> TokenState {
+        TokenState {
+            mint: Pubkey::default(),
+            creator: Pubkey::default(),
+            name: String::new(),
+            symbol: String::new(),
+            uri: String::new(),
+            virtual_sol_reserve: INITIAL_VIRTUAL_SOL,
+            virtual_token_reserve: INITIAL_VIRTUAL_TOKENS,
+            real_sol_reserve: 0,
+            real_token_reserve: TOTAL_SUPPLY,
+            total_burned: 0,
+            volume: 0,
+            creator_fees_accrued: 0,
+            is_graduated: false,
+            pause_flags: 0,
+            created_at: 0,
+            bump: 0,
+            vault_bump: 0,
+            guardian_pause_flags: 0,
+            guardian_paused_until: 0,
+            breaker_reference_price: 0,
+            breaker_reference_slot: 0,
+            breaker_cooldown_until: 0,
+            trading_starts_at: 0,
+            creator_prelaunch_buy: false,
+            allowlist_root: [0; 32],
+            allowlist_ends_at: 0,
+            creator_locked_tokens: 0,
+            creator_cliff_ends_at: 0,
+            creator_vesting_ends_at: 0,
+            metadata_locked: false,
+            guardian_pause_cooldown_until: 0,
+            version: TOKEN_STATE_VERSION,
+        }
+    }
+
+    // Applies a buy the same way `execute_buy_internal` settles it and
+    // returns the gross tokens taken from the curve.
+    fn apply_buy(token_state: &mut TokenState, sol_amount: u64) -> u64 {
+        let (new_sol_reserve, new_token_reserve) = reserves_after_buy(token_state, sol_amount);
+        let tokens_out = token_state.virtual_token_reserve - new_token_reserve;
+        token_state.virtual_sol_reserve = new_sol_reserve;
+        token_state.virtual_token_reserve = new_token_reserve;
+        token_state.real_token_reserve -= tokens_out;
+        token_state.total_burned += burn_fee(tokens_out);
+        tokens_out
+    }
+
+    fn tokens_out(token_state: &TokenState, sol_amount: u64) -> u64 {
+        let (_, new_token_reserve) = reserves_after_buy(token_state, sol_amount);
+        token_state.virtual_token_reserve - new_token_reserve
+    }
+
+    fn graduates_after(config: &GlobalConfig, token_state: &TokenState, sol_amount: u64) -> bool {
+        let mut after = token_state.clone();
+        apply_buy(&mut after, sol_amount);
+        market_cap_usd(config, &after) >= GRADUATION_USD as u128
+    }
+
+    #[test]
+    fn market_cap_bases_price_the_right_supply() {
+        let token_state = new_token_state();
+        let fully_diluted = test_config(MarketCapBasis::FullyDiluted, 200_000_000);
+        let circulating = test_config(MarketCapBasis::Circulating, 200_000_000);
+
+        let expected = (INITIAL_VIRTUAL_SOL as u128 * TOTAL_SUPPLY as u128 / INITIAL_VIRTUAL_TOKENS as u128)
+            * 200_000_000
+            / 1_000_000_000;
+        assert_eq!(market_cap_usd(&fully_diluted, &token_state), expected);
+        // Nothing has left the curve yet.
+        assert_eq!(market_cap_usd(&circulating, &token_state), 0);
+
+        let burned = 1_000_000 * 1_000_000_000;
+        let held = 100_000_000 * 1_000_000_000;
+        let fully_diluted_cap = market_cap_usd_at(
+            &fully_diluted,
+            INITIAL_VIRTUAL_SOL,
+            INITIAL_VIRTUAL_TOKENS,
+            TOTAL_SUPPLY - burned - held,
+            burned,
+        );
+        let circulating_cap = market_cap_usd_at(
+            &circulating,
+            INITIAL_VIRTUAL_SOL,
+            INITIAL_VIRTUAL_TOKENS,
+            TOTAL_SUPPLY - burned - held,
+            burned,
+        );
+        let price_supply = |supply: u64| {
+            (INITIAL_VIRTUAL_SOL as u128 * supply as u128 / INITIAL_VIRTUAL_TOKENS as u128) * 200_000_000
+                / 1_000_000_000
+        };
+        assert_eq!(fully_diluted_cap, price_supply(TOTAL_SUPPLY - burned));
+        assert_eq!(circulating_cap, price_supply(held));
+    }
+
+    #[test]
+    fn partial_fill_lands_on_graduation_boundary() {
+        for market_cap_basis in [MarketCapBasis::FullyDiluted, MarketCapBasis::Circulating] {
+            let config = test_config(market_cap_basis, 200_000_000);
+            let mut token_state = new_token_state();
+
+            // Start from a fresh curve and from one that already has volume.
+            for _ in 0..2 {
+                assert!(market_cap_usd(&config, &token_state) < GRADUATION_USD as u128);
+
+                let fill = max_fillable_sol(&config, &token_state);
+                assert!(fill > 0);
+                assert!(graduates_after(&config, &token_state, fill));
+                assert!(!graduates_after(&config, &token_state, fill - 1));
+                assert!(tokens_out(&token_state, fill) <= token_state.real_token_reserve);
+
+                apply_buy(&mut token_state, fill / 2);
+            }
+        }
+    }
+
+    #[test]
+    fn partial_fill_never_exceeds_real_token_reserve() {
+        // A near-zero SOL price keeps the curve from graduating, so the
+        // remaining real tokens are what bounds the fill.
+        for market_cap_basis in [MarketCapBasis::FullyDiluted, MarketCapBasis::Circulating] {
+            let config = test_config(market_cap_basis, 1);
+            let mut token_state = new_token_state();
+
+            for _ in 0..3 {
+                let fill = max_fillable_sol(&config, &token_state);
+                assert!(!graduates_after(&config, &token_state, fill));
+                assert!(tokens_out(&token_state, fill) <= token_state.real_token_reserve);
+                assert!(tokens_out(&token_state, fill + 1) > token_state.real_token_reserve);
+
+                apply_buy(&mut token_state, fill - fill / 10);
+            }
+
+            let fill = max_fillable_sol(&config, &token_state);
+            apply_buy(&mut token_state, fill);
+            assert!(token_state.real_token_reserve < token_state.virtual_token_reserve);
+        }
+    }
+
+    #[test]
+    fn legacy_pause_keeps_trading_paused_until_migrated() {
+        let mut token_state = new_token_state();
+        token_state.version = 0;
+        token_state.pause_flags = 1;
+        assert_eq!(token_pause_flags(&token_state, 0), PAUSE_TRADING);
+
+        token_state.version = TOKEN_STATE_VERSION;
+        assert_eq!(token_pause_flags(&token_state, 0), PAUSE_BUY);
+
+        let mut config = test_config(MarketCapBasis::FullyDiluted, 200_000_000);
+        config.version = 0;
+        config.pause_flags = 1;
+        assert_eq!(factory_pause_flags(&config, 0), PAUSE_ALL);
+        config.pause_flags = 0;
+        assert_eq!(factory_pause_flags(&config, 0), 0);
+    }
+
+    #[test]
+    fn wallet_cap_window_starts_when_trading_opens() {
+        let mut config = test_config(MarketCapBasis::FullyDiluted, 200_000_000);
+        config.max_wallet_buy_bp = 100;
+        config.wallet_cap_duration = 60;
+        let mut token_state = new_token_state();
+        token_state.created_at = 1_000;
+        token_state.trading_starts_at = 1_000 + 3 * 24 * 60 * 60;
+
+        assert!(wallet_cap_active(&config, &token_state, token_state.trading_starts_at));
+        assert!(wallet_cap_active(&config, &token_state, token_state.trading_starts_at + 59));
+        assert!(!wallet_cap_active(&config, &token_state, token_state.trading_starts_at + 60));
+    }
+
+    #[test]
+    fn sandwich_guard_window_starts_when_trading_opens() {
+        let mut config = test_config(MarketCapBasis::FullyDiluted, 200_000_000);
+        config.sandwich_guard_slots = 2;
+        config.sandwich_guard_duration = 60;
+        let mut token_state = new_token_state();
+        token_state.created_at = 1_000;
+        token_state.trading_starts_at = 1_000 + 3 * 24 * 60 * 60;
+        let user = Pubkey::new_unique();
+        let mut trade_guard = TradeGuard {
+            mint: token_state.mint,
+            user,
+            last_trade_slot: 10,
+            last_trade_was_buy: true,
+            bump: 0,
+        };
+        let check = |is_buy, now, slot| TradeGuardCheck { user, bump: 0, is_buy, now, slot };
+
+        let now = token_state.trading_starts_at + 30;
+        assert!(enforce_trade_guard(&config, &token_state, &mut trade_guard, check(false, now, 11)).is_err());
+        assert!(enforce_trade_guard(&config, &token_state, &mut trade_guard, check(false, now, 12)).is_ok());
+
+        let now = token_state.trading_starts_at + 60;
+        assert!(enforce_trade_guard(&config, &token_state, &mut trade_guard, check(true, now, 12)).is_ok());
+    }
+
+    fn allowlist_node(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&[1]);
+        hasher.update(&left);
+        hasher.update(&right);
+        *hasher.finalize().as_bytes()
+    }
+
+    #[test]
+    fn allowlist_proof_verifies_only_the_listed_cap() {
+        let (alice, bob, carol) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let alice_leaf = allowlist_leaf(&alice, 1_000);
+        let bob_leaf = allowlist_leaf(&bob, 2_000);
+        let carol_leaf = allowlist_leaf(&carol, 3_000);
+        let root = allowlist_node(allowlist_node(alice_leaf, bob_leaf), carol_leaf);
+
+        let proof = AllowlistProof {
+            max_tokens: 1_000,
+            proof: vec![bob_leaf, carol_leaf],
+        };
+        assert!(verify_allowlist_proof(&root, &alice, &proof));
+        assert!(!verify_allowlist_proof(&root, &bob, &proof));
+
+        let wrong_cap = AllowlistProof {
+            max_tokens: 1_001,
+            proof: proof.proof.clone(),
+        };
+        assert!(!verify_allowlist_proof(&root, &alice, &wrong_cap));
+
+        let mut tampered = AllowlistProof {
+            max_tokens: 1_000,
+            proof: proof.proof.clone(),
+        };
+        tampered.proof[0][0] ^= 1;
+        assert!(!verify_allowlist_proof(&root, &alice, &tampered));
+
+        let carol_proof = AllowlistProof {
+            max_tokens: 3_000,
+            proof: vec![allowlist_node(alice_leaf, bob_leaf)],
+        };
+        assert!(verify_allowlist_proof(&root, &carol, &carol_proof));
+
+        let too_long = AllowlistProof {
+            max_tokens: 1_000,
+            proof: vec![[0; 32]; MAX_ALLOWLIST_PROOF_LEN + 1],
+        };
+        assert!(!verify_allowlist_proof(&root, &alice, &too_long));
+    }
+
+    #[test]
+    fn vested_amount_follows_cliff_and_linear_schedule() {
+        let creator_vesting = CreatorVesting {
+            mint: Pubkey::default(),
+            creator: Pubkey::default(),
+            total_amount: 4_000,
+            claimed_amount: 0,
+            start_at: 1_000,
+            cliff_ends_at: 1_100,
+            ends_at: 1_400,
+            bump: 0,
+        };
+
+        assert_eq!(vested_amount(&creator_vesting, 1_000), 0);
+        assert_eq!(vested_amount(&creator_vesting, 1_099), 0);
+        // The cliff releases everything accrued since the start at once.
+        assert_eq!(vested_amount(&creator_vesting, 1_100), 1_000);
+        assert_eq!(vested_amount(&creator_vesting, 1_200), 2_000);
+        assert_eq!(vested_amount(&creator_vesting, 1_399), 3_990);
+        assert_eq!(vested_amount(&creator_vesting, 1_400), 4_000);
+        assert_eq!(vested_amount(&creator_vesting, i64::MAX), 4_000);
+    }
+
+    #[test]
+    fn record_sell_realizes_pnl_on_tracked_tokens_only() {
+        let mut user_position = UserPosition {
+            mint: Pubkey::default(),
+            user: Pubkey::default(),
+            tokens_bought: 0,
+            tokens_sold: 0,
+            tokens_held: 0,
+            sol_spent: 0,
+            sol_received: 0,
+            cost_basis: 0,
+            avg_cost_price: 0,
+            realized_pnl: 0,
+            first_trade_at: 0,
+            last_trade_at: 0,
+            bump: 0,
+        };
+        record_buy(&mut user_position, 1_000, 100, 10);
+        record_buy(&mut user_position, 1_000, 300, 20);
+        assert_eq!(user_position.cost_basis, 400);
+
+        // Partial sell: a quarter of the tracked tokens carries a quarter of
+        // the cost basis.
+        record_sell(&mut user_position, 500, 150, 30);
+        assert_eq!(user_position.tokens_held, 1_500);
+        assert_eq!(user_position.tokens_sold, 500);
+        assert_eq!(user_position.cost_basis, 300);
+        assert_eq!(user_position.sol_received, 150);
+        assert_eq!(user_position.realized_pnl, 50);
+        assert_eq!(user_position.avg_cost_price, 300 * PRICE_PRECISION / 1_500);
+
+        // Selling 500 tokens received elsewhere on top of the tracked ones
+        // only realizes the proceeds attributable to tracked tokens.
+        record_sell(&mut user_position, 2_000, 400, 40);
+        assert_eq!(user_position.tokens_held, 0);
+        assert_eq!(user_position.tokens_sold, 2_500);
+        assert_eq!(user_position.cost_basis, 0);
+        assert_eq!(user_position.sol_received, 550);
+        assert_eq!(user_position.realized_pnl, 50);
+        assert_eq!(user_position.avg_cost_price, 0);
+        assert_eq!(user_position.first_trade_at, 10);
+        assert_eq!(user_position.last_trade_at, 40);
+    }
+
+    #[test]
+    fn normalize_symbol_folds_case_and_lookalike_digits() {
+        assert_eq!(normalize_symbol("BONK"), "BONK");
+        assert_eq!(normalize_symbol("bonk"), "BONK");
+        assert_eq!(normalize_symbol("B0NK"), "BONK");
+        assert_eq!(normalize_symbol("1l1L"), "IIII");
+        assert_eq!(normalize_symbol("5S8b"), "SSBB");
+        assert_eq!(normalize_symbol("W1F"), normalize_symbol("wif"));
+        assert_ne!(normalize_symbol("PEPE2"), normalize_symbol("PEPE"));
+    }
+
+    #[test]
+    fn pow_leading_zeros_counts_bits_across_bytes() {
+        let creator = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let hash_of = |pow: &LaunchPow| {
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(creator.as_ref());
+            hasher.update(mint.as_ref());
+            hasher.update(&pow.slot.to_le_bytes());
+            hasher.update(&pow.nonce.to_le_bytes());
+            *hasher.finalize().as_bytes()
+        };
+        let find = |matches: &dyn Fn(&[u8; 32]) -> bool| {
+            (0..)
+                .map(|nonce| LaunchPow { slot: 42, nonce })
+                .find(|pow| matches(&hash_of(pow)))
+                .unwrap()
+        };
+
+        let pow = find(&|hash| hash[0] != 0);
+        assert_eq!(
+            pow_leading_zeros(&creator, &mint, &pow),
+            hash_of(&pow)[0].leading_zeros()
+        );
+
+        // A zero first byte must carry the count into the next byte.
+        let pow = find(&|hash| hash[0] == 0 && hash[1] != 0);
+        assert_eq!(
+            pow_leading_zeros(&creator, &mint, &pow),
+            8 + hash_of(&pow)[1].leading_zeros()
+        );
+    }
+
+    #[test]
+    fn launch_claim_is_exclusive_until_it_expires() {
+        let (first, copycat, earlier) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut claim = LaunchClaim {
+            mint: Pubkey::default(),
+            committed_slot: 0,
+            exclusive_until: 0,
+            bump: 0,
+        };
+
+        // A plain create neither needs nor takes a claim.
+        assert!(claim_launch_identity(&mut claim, first, None, 0, 7).is_ok());
+        assert_eq!(claim.mint, Pubkey::default());
+
+        assert!(claim_launch_identity(&mut claim, first, Some(100), 0, 7).is_ok());
+        assert_eq!(claim.mint, first);
+        assert_eq!(claim.exclusive_until, LAUNCH_EXCLUSIVITY_DURATION);
+
+        let claimed: Error = BonkrError::LaunchIdentityClaimed.into();
+        assert_eq!(claim_launch_identity(&mut claim, copycat, None, 10, 7).unwrap_err(), claimed);
+        assert_eq!(claim_launch_identity(&mut claim, copycat, Some(200), 10, 7).unwrap_err(), claimed);
+        assert!(claim_launch_identity(&mut claim, first, None, 10, 7).is_ok());
+        assert_eq!(claim.mint, first);
+
+        // An older commitment wins the name back within the window.
+        assert!(claim_launch_identity(&mut claim, earlier, Some(50), 20, 7).is_ok());
+        assert_eq!(claim.mint, earlier);
+        assert_eq!(claim.committed_slot, 50);
+
+        let expires_at = 20 + LAUNCH_EXCLUSIVITY_DURATION;
+        assert!(claim_launch_identity(&mut claim, copycat, None, expires_at - 1, 7).is_err());
+        assert!(claim_launch_identity(&mut claim, copycat, None, expires_at, 7).is_ok());
+    }
 }
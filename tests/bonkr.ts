@@ -0,0 +1,106 @@
+// Run against a local validator, never the mainnet cluster set in Anchor.toml:
+//   anchor test --provider.cluster localnet
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getMint,
+} from "@solana/spl-token";
+import { Keypair, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js";
+import { blake3 } from "@noble/hashes/blake3";
+import { expect } from "chai";
+import { Bonkr } from "../target/types/bonkr";
+
+const TOTAL_SUPPLY = new BN("1000000000000000000");
+
+describe("bonkr", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Bonkr as Program<Bonkr>;
+  const creator = provider.wallet.publicKey;
+
+  const pda = (...seeds: (Buffer | Uint8Array)[]) =>
+    PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+
+  const config = pda(Buffer.from("config"));
+
+  before(async () => {
+    if ((await provider.connection.getAccountInfo(config)) === null) {
+      await program.methods
+        .initialize(new BN(0), new BN(0))
+        .accountsPartial({
+          config,
+          authority: creator,
+          platformFeeRecipient: creator,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+  });
+
+  it("creates a token with a fixed supply", async () => {
+    const name = "Bonkr Test";
+    const symbol = "BONKT";
+    const mint = Keypair.generate();
+    const tokenState = pda(Buffer.from("token_state"), mint.publicKey.toBuffer());
+    const { tokenCount, platformFeeRecipient } = await program.account.globalConfig.fetch(config);
+
+    await program.methods
+      .createToken({
+        name,
+        symbol,
+        uri: "https://bonkr.fun/test.json",
+        initialBuySol: new BN(0),
+        initialMinTokensOut: new BN(0),
+        tradingStartsAt: null,
+        creatorPrelaunchBuy: false,
+        vesting: null,
+        profile: null,
+        pow: null,
+        allowlist: null,
+        deadlineUnixTs: null,
+        maxSlot: null,
+      })
+      .accountsPartial({
+        config,
+        mint: mint.publicKey,
+        tokenState,
+        tokenIndex: pda(Buffer.from("token_index"), tokenCount.toArrayLike(Buffer, "le", 8)),
+        tokenVault: getAssociatedTokenAddressSync(mint.publicKey, tokenState, true),
+        solVault: pda(Buffer.from("sol_vault"), mint.publicKey.toBuffer()),
+        creatorTokenAccount: getAssociatedTokenAddressSync(mint.publicKey, creator),
+        creatorPosition: pda(
+          Buffer.from("user_position"),
+          mint.publicKey.toBuffer(),
+          creator.toBuffer()
+        ),
+        creatorVesting: null,
+        vestingTokenAccount: null,
+        tokenProfile: null,
+        symbolRecord: null,
+        launchCommitment: null,
+        nameClaim: pda(
+          Buffer.from("name_claim"),
+          blake3(Buffer.from(name.trim().toLowerCase()))
+        ),
+        symbolClaim: pda(Buffer.from("symbol_claim"), Buffer.from("BONKT")),
+        creatorProfile: pda(Buffer.from("creator_profile"), creator.toBuffer()),
+        creator,
+        platformFeeRecipient,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([mint])
+      .rpc();
+
+    const mintAccount = await getMint(provider.connection, mint.publicKey);
+    expect(mintAccount.mintAuthority).to.be.null;
+    expect(mintAccount.freezeAuthority).to.be.null;
+    expect(mintAccount.supply.toString()).to.equal(TOTAL_SUPPLY.toString());
+  });
+});